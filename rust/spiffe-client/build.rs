@@ -0,0 +1,9 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tonic_build::configure()
+        .build_server(false)
+        .compile_protos(
+            &["proto/spiffe/workload/workload.proto"],
+            &["proto"],
+        )?;
+    Ok(())
+}
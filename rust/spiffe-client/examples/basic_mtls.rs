@@ -71,6 +71,7 @@ fn create_mock_svid(spiffe_id: SpiffeId) -> Result<X509Svid, Box<dyn Error>> {
         vec![0x30, 0x82], // Mock certificate data
     ];
     let private_key = vec![0x30, 0x82]; // Mock private key
+    let not_before = Utc::now();
     let expiry = Utc::now() + chrono::Duration::hours(24);
     let serial_number = "1234567890".to_string();
 
@@ -78,6 +79,7 @@ fn create_mock_svid(spiffe_id: SpiffeId) -> Result<X509Svid, Box<dyn Error>> {
         spiffe_id,
         cert_chain,
         private_key,
+        not_before,
         expiry,
         serial_number,
     )?)
@@ -0,0 +1,73 @@
+//! Example binding streamed SVID rotations into a running mTLS configuration
+//!
+//! Shows how [`ManagedWorkloadClient::watch_x509_svids`] pairs each rotated
+//! SVID with its trust bundle, and how those pairs feed straight into
+//! `MtlsConfig::update_svid`/`update_bundle` so the `RotatableIdentity` and
+//! `SwappableRootStore` backing a long-lived `ServerCertVerifier`/client-auth
+//! resolver pick up the new materials at the next handshake, without ever
+//! rebuilding the `ClientConfig`/`ServerConfig`.
+
+use futures::StreamExt;
+use spiffe_client::{ManagedWorkloadClient, MtlsConfig, WorkloadApiConfig};
+use std::error::Error;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    tracing_subscriber::fmt()
+        .with_env_filter("info")
+        .init();
+
+    println!("SPIRE Client - Watch Rotation Example\n");
+
+    let config = WorkloadApiConfig {
+        auto_rotate: true,
+        ..Default::default()
+    };
+
+    let managed = match ManagedWorkloadClient::new(config).await {
+        Ok(managed) => managed,
+        Err(e) => {
+            println!("✗ Failed to create managed client: {}", e);
+            println!("  Make sure SPIRE agent is running at /tmp/spire-agent/public/api.sock");
+            return Ok(());
+        }
+    };
+
+    // Seed the mTLS config from whichever SVID/bundle arrives first.
+    let mut rotations = managed.watch_x509_svids();
+    let Some((svid, bundle)) = rotations.next().await else {
+        println!("✗ Watch stream ended before delivering an SVID");
+        return Ok(());
+    };
+
+    let mtls_config = Arc::new(RwLock::new(MtlsConfig::from_svid(&svid, &bundle)?));
+    println!("✓ mTLS config seeded for {}", svid.spiffe_id());
+
+    // Every subsequent rotation hot-swaps the identity/trust roots in place;
+    // any in-flight connections keep running against the config they already
+    // negotiated, and the next handshake picks up the fresh materials.
+    tokio::spawn({
+        let mtls_config = mtls_config.clone();
+        async move {
+            while let Some((svid, bundle)) = rotations.next().await {
+                let mut config = mtls_config.write().await;
+                if let Err(e) = config.update_svid(&svid, &bundle) {
+                    println!("✗ Failed to rotate identity: {}", e);
+                    continue;
+                }
+                if let Err(e) = config.update_bundle(&bundle) {
+                    println!("✗ Failed to rotate trust bundle: {}", e);
+                    continue;
+                }
+                println!("✓ Rotated mTLS materials for {}", svid.spiffe_id());
+            }
+        }
+    });
+
+    managed.shutdown().await;
+    println!("\n✓ Watch rotation example finished");
+
+    Ok(())
+}
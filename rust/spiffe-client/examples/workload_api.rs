@@ -86,17 +86,21 @@ async fn main() -> Result<(), Box<dyn Error>> {
     let config = WorkloadApiConfig {
         socket_path: "/tmp/spire-agent/public/api.sock".to_string(),
         auto_rotate: true,
-        rotation_interval: 300, // 5 minutes
+        rotation_threshold: 0.5,
+        pre_expiry_margin: std::time::Duration::from_secs(60),
         cache_bundles: true,
+        backoff: Default::default(),
     };
 
     match ManagedWorkloadClient::new(config).await {
         Ok(managed) => {
             println!("✓ Created managed client with:");
-            println!("  - Auto-rotation: enabled");
-            println!("  - Rotation interval: 5 minutes");
+            println!("  - Auto-rotation: enabled (agent push stream)");
             println!("  - Bundle caching: enabled");
 
+            // Subscribe to SVID rotations pushed by the agent
+            let mut svid_updates = managed.subscribe();
+
             // Use the client
             let client = managed.client();
 
@@ -111,13 +115,9 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 }
             }
 
-            // Check cached bundle
-            if let Some(bundle) = client.get_svid_bundle().await {
-                println!("\n✓ Cached SVID bundle available");
-                if bundle.needs_rotation() {
-                    println!("  ⚠ Rotation needed soon");
-                } else {
-                    println!("  ✓ No rotation needed yet");
+            if svid_updates.changed().await.is_ok() {
+                if let Some(svid) = svid_updates.borrow().as_ref() {
+                    println!("\n✓ Received rotated SVID for {}", svid.spiffe_id());
                 }
             }
 
@@ -3,7 +3,11 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, SanType, KeyPair, SignatureAlgorithm};
-use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use spiffe_client::{SpiffeId, SpiffeIdMatcher};
+use std::collections::HashMap;
 use std::fs;
 use std::net::SocketAddr;
 use std::path::Path;
@@ -13,8 +17,249 @@ use tokio::net::TcpStream;
 use tokio_rustls::rustls::{self, ClientConfig};
 use tokio_rustls::TlsConnector;
 use tracing::{info, error};
+use webpki::{EndEntityCert, KeyUsage};
 use x509_parser::prelude::*;
 
+/// Validate `end_entity`'s chain against `roots`, with no hostname/SAN check
+///
+/// SPIFFE SVIDs carry only a URI SAN, never a DNS/IP SAN, so
+/// `rustls::client::WebPkiServerVerifier` (which always checks the leaf
+/// against a `ServerName`) can never accept one — there is no name to match.
+/// This builds the `webpki` end-entity cert directly and calls
+/// `verify_for_usage` with no server name, leaving identity binding to the
+/// caller's own SPIFFE ID check.
+fn verify_chain_for_server_auth(
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    roots: &rustls::RootCertStore,
+    now: UnixTime,
+) -> Result<(), rustls::Error> {
+    let cert = EndEntityCert::try_from(end_entity)
+        .map_err(|e| rustls::Error::General(format!("Invalid end-entity certificate: {:?}", e)))?;
+
+    let sig_algs = rustls::crypto::ring::default_provider().signature_verification_algorithms;
+
+    cert.verify_for_usage(sig_algs.all, &roots.roots, intermediates, now, KeyUsage::server_auth(), None)
+        .map(|_| ())
+        .map_err(|e| rustls::Error::General(format!("Certificate chain validation failed: {:?}", e)))
+}
+
+/// Policy deciding whether the server's SPIFFE ID is allowed to be trusted
+///
+/// Plugs into [`SpiffeServerCertVerifier`] so identity policy is enforced
+/// during the handshake instead of as an afterthought once the connection
+/// is already established.
+trait SpiffeIdAuthorizer: Send + Sync {
+    /// Returns `true` if `spiffe_id` is allowed to be trusted
+    fn authorize(&self, spiffe_id: &str) -> bool;
+}
+
+/// Authorize any peer presenting a well-formed SPIFFE ID
+struct AllowAny;
+
+impl SpiffeIdAuthorizer for AllowAny {
+    fn authorize(&self, _spiffe_id: &str) -> bool {
+        true
+    }
+}
+
+/// Authorize any peer belonging to the given trust domain
+struct TrustDomainAuthorizer(String);
+
+impl SpiffeIdAuthorizer for TrustDomainAuthorizer {
+    fn authorize(&self, spiffe_id: &str) -> bool {
+        trust_domain_of(spiffe_id)
+            .map(|domain| domain == self.0)
+            .unwrap_or(false)
+    }
+}
+
+/// Authorize peers matching a [`SpiffeIdMatcher`] pattern, e.g.
+/// `spiffe://example.org/ns/*/sa/web`
+struct MatcherAuthorizer(SpiffeIdMatcher);
+
+impl SpiffeIdAuthorizer for MatcherAuthorizer {
+    fn authorize(&self, spiffe_id: &str) -> bool {
+        SpiffeId::parse(spiffe_id)
+            .map(|id| self.0.matches(&id))
+            .unwrap_or(false)
+    }
+}
+
+/// The trust domain segment of a `spiffe://trust-domain/path` identity
+fn trust_domain_of(spiffe_id: &str) -> Option<&str> {
+    spiffe_id.strip_prefix("spiffe://")?.split('/').next()
+}
+
+/// Trust anchors kept isolated per trust domain rather than merged into one
+/// flat `RootCertStore`
+///
+/// A leaf's declared trust domain (from its SPIFFE ID) selects which
+/// domain's anchors validate its chain, so one domain's CA can never vouch
+/// for another domain's identities — matching how federated meshes isolate
+/// trust per domain.
+#[derive(Default)]
+struct FederatedTrustBundles(HashMap<String, rustls::RootCertStore>);
+
+impl FederatedTrustBundles {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register (or replace) the anchors trusted for `trust_domain`
+    fn register(&mut self, trust_domain: impl Into<String>, root_store: rustls::RootCertStore) {
+        self.0.insert(trust_domain.into(), root_store);
+    }
+
+    /// The anchors registered for `trust_domain`, if any
+    fn get(&self, trust_domain: &str) -> Option<&rustls::RootCertStore> {
+        self.0.get(trust_domain)
+    }
+}
+
+/// Extract the single `spiffe://` URI SAN from a DER-encoded leaf certificate
+fn extract_spiffe_id_from_cert(cert_der: &CertificateDer) -> Result<String> {
+    let (_, cert) = X509Certificate::from_der(cert_der.as_ref())
+        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+
+    for ext in cert.extensions() {
+        if ext.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME {
+            if let ParsedExtension::SubjectAlternativeName(san) = &ext.parsed_extension() {
+                for name in &san.general_names {
+                    if let GeneralName::URI(uri) = name {
+                        if uri.starts_with("spiffe://") {
+                            return Ok(uri.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!("No SPIFFE ID found in certificate"))
+}
+
+/// rustls `ServerCertVerifier` that authorizes the server's SPIFFE ID
+/// *during* the handshake, after standard WebPKI chain validation
+///
+/// Trust anchors are kept per-domain in [`FederatedTrustBundles`]: the
+/// leaf's declared trust domain selects which domain's anchors validate the
+/// chain, so a leaf claiming a domain with no registered bundle is rejected
+/// outright instead of falling through to whatever anchors happen to be in
+/// a shared store.
+struct SpiffeServerCertVerifier {
+    trust_bundles: FederatedTrustBundles,
+    authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeServerCertVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpiffeServerCertVerifier").finish_non_exhaustive()
+    }
+}
+
+impl SpiffeServerCertVerifier {
+    /// The anchors registered for `cert`'s declared trust domain, or an
+    /// error if the domain has no registered bundle
+    fn anchors_for(&self, cert: &CertificateDer) -> Result<Arc<rustls::RootCertStore>, rustls::Error> {
+        let spiffe_id = extract_spiffe_id_from_cert(cert)
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+        let trust_domain = trust_domain_of(&spiffe_id)
+            .ok_or_else(|| rustls::Error::General(format!("Malformed SPIFFE ID: {}", spiffe_id)))?;
+        let root_store = self.trust_bundles.get(trust_domain).ok_or_else(|| {
+            rustls::Error::General(format!(
+                "No trust bundle registered for trust domain '{}'",
+                trust_domain
+            ))
+        })?;
+        Ok(Arc::new(root_store.clone()))
+    }
+}
+
+impl ServerCertVerifier for SpiffeServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // Chain validation happens only against the anchors registered for
+        // the leaf's own declared trust domain; one domain's CA never
+        // vouches for another domain's identities. SPIFFE SVIDs carry no DNS
+        // name, so identity binding happens below instead of a hostname
+        // check against `server_name`.
+        let _ = (server_name, ocsp_response);
+        let anchors = self.anchors_for(end_entity)?;
+        verify_chain_for_server_auth(end_entity, intermediates, &anchors, now)?;
+
+        let spiffe_id = extract_spiffe_id_from_cert(end_entity)
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+
+        if !self.authorizer.authorize(&spiffe_id) {
+            return Err(rustls::Error::General(format!(
+                "SPIFFE ID not authorized: {}",
+                spiffe_id
+            )));
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::client::WebPkiServerVerifier::builder(self.anchors_for(cert)?)
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::client::WebPkiServerVerifier::builder(self.anchors_for(cert)?)
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+        ]
+    }
+}
+
+/// Build the authorizer implied by the CLI flags.
+///
+/// `--expected-server-spiffe-id` may be an exact SPIFFE ID or a
+/// `/`-segmented pattern with `*` wildcards (e.g. `spiffe://example.org/ns/*/sa/web`),
+/// matched via [`SpiffeIdMatcher`]; a pattern with no wildcard segments
+/// behaves as an exact match. With no flag given, any identity within
+/// `--trust-domain` is authorized.
+fn build_authorizer(args: &Args) -> Result<Arc<dyn SpiffeIdAuthorizer>> {
+    match &args.expected_server_spiffe_id {
+        Some(pattern) => {
+            let matcher = SpiffeIdMatcher::parse(pattern)
+                .map_err(|e| anyhow::anyhow!("Invalid --expected-server-spiffe-id pattern: {}", e))?;
+            Ok(Arc::new(MatcherAuthorizer(matcher)))
+        }
+        None => Ok(Arc::new(TrustDomainAuthorizer(args.trust_domain.clone()))),
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -54,6 +299,11 @@ struct Args {
     #[arg(long, default_value = "example.org")]
     trust_domain: String,
 
+    /// Federated trust domain vouched for by the Go CA, isolated from
+    /// `--trust-domain`'s own bundle
+    #[arg(long, default_value = "go.example.org")]
+    go_trust_domain: String,
+
     /// Client SPIFFE ID
     #[arg(long, default_value = "spiffe://example.org/rust-client")]
     client_spiffe_id: String,
@@ -102,26 +352,16 @@ async fn main() -> Result<()> {
 
     info!("✓ mTLS handshake successful");
 
-    // Verify server certificate and SPIFFE ID
+    // The server's SPIFFE ID was already authorized during the handshake by
+    // `SpiffeServerCertVerifier`; this just logs which identity we connected to.
     let (_, client_connection) = tls_stream.get_ref();
     if let Some(certs) = client_connection.peer_certificates() {
-        info!("Server presented {} certificate(s)", certs.len());
-
-        if !certs.is_empty() {
-            match verify_spiffe_certificate(&certs[0], &args.trust_domain, args.expected_server_spiffe_id.as_deref()) {
-                Ok(spiffe_id) => {
-                    info!("✓ Server SPIFFE ID verified: {}", spiffe_id);
-                    info!("✓ Server certificate verified");
-                }
-                Err(e) => {
-                    error!("✗ Server SPIFFE verification failed: {}", e);
-                    return Err(anyhow::anyhow!("Server SPIFFE verification failed: {}", e));
-                }
+        if let Some(leaf) = certs.first() {
+            match extract_spiffe_id_from_cert(leaf) {
+                Ok(spiffe_id) => info!("✓ Server SPIFFE ID: {}", spiffe_id),
+                Err(e) => error!("Could not re-extract server SPIFFE ID for logging: {}", e),
             }
         }
-    } else {
-        error!("No server certificates presented");
-        return Err(anyhow::anyhow!("No server certificates presented"));
     }
 
     // Send test messages
@@ -248,20 +488,25 @@ fn create_client_config(
     let key_der = PrivateKeyDer::try_from(key.contents().to_vec())
         .map_err(|_| anyhow::anyhow!("Failed to parse private key"))?;
 
-    // Create root cert store for server verification (Trust Bundle)
-    let mut root_store = rustls::RootCertStore::empty();
+    // Register each CA's anchors under its own trust domain instead of a
+    // single flat store, so the Rust CA can never vouch for a Go-domain
+    // identity or vice versa.
+    let mut trust_bundles = FederatedTrustBundles::new();
 
-    // Add Rust CA certificate
-    root_store.add(CertificateDer::from(ca.contents().to_vec()))
+    let mut rust_root_store = rustls::RootCertStore::empty();
+    rust_root_store.add(CertificateDer::from(ca.contents().to_vec()))
         .map_err(|e| anyhow::anyhow!("Failed to add Rust CA cert: {:?}", e))?;
-    info!("✓ Added Rust CA to trust bundle");
+    trust_bundles.register(args.trust_domain.clone(), rust_root_store);
+    info!("✓ Registered Rust CA for trust domain '{}'", args.trust_domain);
 
-    // Try to add Go CA certificate to trust bundle
+    // Try to register the Go CA's anchors under its own federated domain
     let go_ca_path = Path::new(&args.cert_dir).join(&args.go_ca_cert);
     if let Ok(go_ca_pem) = std::fs::read(go_ca_path) {
         if let Ok(go_ca) = ::pem::parse(go_ca_pem) {
-            if let Ok(()) = root_store.add(CertificateDer::from(go_ca.contents().to_vec())) {
-                info!("✓ Added Go CA to trust bundle");
+            let mut go_root_store = rustls::RootCertStore::empty();
+            if let Ok(()) = go_root_store.add(CertificateDer::from(go_ca.contents().to_vec())) {
+                trust_bundles.register(args.go_trust_domain.clone(), go_root_store);
+                info!("✓ Registered Go CA for trust domain '{}'", args.go_trust_domain);
             } else {
                 info!("⚠ Failed to parse Go CA certificate");
             }
@@ -272,9 +517,15 @@ fn create_client_config(
         info!("⚠ Go CA certificate not found");
     }
 
-    // Build client config with mTLS
+    // Build client config with handshake-time SPIFFE authorization
+    let server_verifier = Arc::new(SpiffeServerCertVerifier {
+        trust_bundles,
+        authorizer: build_authorizer(args)?,
+    });
+
     let config = ClientConfig::builder()
-        .with_root_certificates(root_store)
+        .dangerous()
+        .with_custom_certificate_verifier(server_verifier)
         .with_client_auth_cert(vec![cert_der], key_der)
         .map_err(|e| anyhow::anyhow!("Failed to build client config: {:?}", e))?;
 
@@ -310,71 +561,3 @@ fn load_certs(args: &Args) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>)> {
 }
 
 use std::net::ToSocketAddrs;
-
-/// Verify SPIFFE certificate and extract SPIFFE ID
-fn verify_spiffe_certificate(cert_der: &CertificateDer, expected_trust_domain: &str, expected_spiffe_id: Option<&str>) -> Result<String> {
-    // Parse the certificate
-    let cert_bytes = cert_der.as_ref();
-    let (_, cert) = X509Certificate::from_der(cert_bytes)
-        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
-
-    // Extract SPIFFE ID from SAN (Subject Alternative Name)
-    let mut spiffe_id = None;
-
-    for ext in cert.extensions() {
-        if ext.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME {
-            if let ParsedExtension::SubjectAlternativeName(san) = &ext.parsed_extension() {
-                for name in &san.general_names {
-                    if let GeneralName::URI(uri) = name {
-                        if uri.starts_with("spiffe://") {
-                            spiffe_id = Some(uri.to_string());
-                            break;
-                        }
-                    }
-                }
-            }
-            break;
-        }
-    }
-
-    let spiffe_id = spiffe_id.ok_or_else(|| anyhow::anyhow!("No SPIFFE ID found in certificate"))?;
-
-    // Validate SPIFFE ID format
-    if !spiffe_id.starts_with("spiffe://") {
-        return Err(anyhow::anyhow!("Invalid SPIFFE ID format: {}", spiffe_id));
-    }
-
-    // Extract trust domain from SPIFFE ID
-    let spiffe_parts: Vec<&str> = spiffe_id.strip_prefix("spiffe://").unwrap().split('/').collect();
-    if spiffe_parts.is_empty() {
-        return Err(anyhow::anyhow!("Invalid SPIFFE ID: missing trust domain"));
-    }
-
-    let trust_domain = spiffe_parts[0];
-
-    // Verify trust domain matches expected
-    if trust_domain != expected_trust_domain {
-        return Err(anyhow::anyhow!(
-            "Trust domain mismatch: expected '{}', found '{}'",
-            expected_trust_domain,
-            trust_domain
-        ));
-    }
-
-    // If specific SPIFFE ID is expected, verify it matches
-    if let Some(expected) = expected_spiffe_id {
-        if spiffe_id != expected {
-            return Err(anyhow::anyhow!(
-                "SPIFFE ID mismatch: expected '{}', found '{}'",
-                expected,
-                spiffe_id
-            ));
-        }
-    }
-
-    info!("✓ SPIFFE ID validation passed");
-    info!("  - SPIFFE ID: {}", spiffe_id);
-    info!("  - Trust Domain: {}", trust_domain);
-
-    Ok(spiffe_id)
-}
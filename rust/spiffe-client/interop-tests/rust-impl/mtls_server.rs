@@ -3,7 +3,11 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use rcgen::{Certificate, CertificateParams, DistinguishedName, DnType, SanType, KeyPair, SignatureAlgorithm};
-use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::collections::HashSet;
 use std::fs;
 use std::net::SocketAddr;
 use std::path::Path;
@@ -12,9 +16,198 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::net::{TcpListener, TcpStream};
 use tokio_rustls::rustls::{self, ServerConfig};
 use tokio_rustls::TlsAcceptor;
-use tracing::{info, warn, error};
+use tracing::{info, warn};
 use x509_parser::prelude::*;
 
+/// Policy deciding whether a connecting client's SPIFFE ID is allowed
+///
+/// Plugs into [`SpiffeClientCertVerifier`] so identity policy is enforced
+/// during the handshake instead of as an afterthought once the connection
+/// is already established.
+trait SpiffeIdAuthorizer: Send + Sync {
+    /// Returns `true` if `spiffe_id` is allowed to connect
+    fn authorize(&self, spiffe_id: &str) -> bool;
+}
+
+/// Authorize any peer presenting a well-formed SPIFFE ID
+struct AllowAny;
+
+impl SpiffeIdAuthorizer for AllowAny {
+    fn authorize(&self, _spiffe_id: &str) -> bool {
+        true
+    }
+}
+
+/// Authorize only peers whose ID is one of an explicit set
+struct AllowList(HashSet<String>);
+
+impl SpiffeIdAuthorizer for AllowList {
+    fn authorize(&self, spiffe_id: &str) -> bool {
+        self.0.contains(spiffe_id)
+    }
+}
+
+/// Authorize any peer belonging to the given trust domain
+struct TrustDomainAuthorizer(String);
+
+impl SpiffeIdAuthorizer for TrustDomainAuthorizer {
+    fn authorize(&self, spiffe_id: &str) -> bool {
+        spiffe_id
+            .strip_prefix("spiffe://")
+            .and_then(|rest| rest.split('/').next())
+            .map(|domain| domain == self.0)
+            .unwrap_or(false)
+    }
+}
+
+/// Authorize peers whose path matches a `/`-segmented glob pattern
+///
+/// `*` matches exactly one path segment, e.g. `/ns/*/sa/web` matches
+/// `spiffe://example.org/ns/payments/sa/web` but not a deeper path.
+struct PathGlobAuthorizer {
+    trust_domain: String,
+    pattern: String,
+}
+
+impl SpiffeIdAuthorizer for PathGlobAuthorizer {
+    fn authorize(&self, spiffe_id: &str) -> bool {
+        let Some(rest) = spiffe_id.strip_prefix("spiffe://") else {
+            return false;
+        };
+        let mut parts = rest.splitn(2, '/');
+        if parts.next() != Some(self.trust_domain.as_str()) {
+            return false;
+        }
+        let path = parts.next().unwrap_or("");
+
+        let path_segs: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let pattern_segs: Vec<&str> = self.pattern.split('/').filter(|s| !s.is_empty()).collect();
+        path_segs.len() == pattern_segs.len()
+            && path_segs
+                .iter()
+                .zip(pattern_segs.iter())
+                .all(|(seg, pat)| *pat == "*" || seg == pat)
+    }
+}
+
+/// Extract the single `spiffe://` URI SAN from a DER-encoded leaf certificate
+fn extract_spiffe_id_from_cert(cert_der: &CertificateDer) -> Result<String> {
+    let (_, cert) = X509Certificate::from_der(cert_der.as_ref())
+        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+
+    for ext in cert.extensions() {
+        if ext.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME {
+            if let ParsedExtension::SubjectAlternativeName(san) = &ext.parsed_extension() {
+                for name in &san.general_names {
+                    if let GeneralName::URI(uri) = name {
+                        if uri.starts_with("spiffe://") {
+                            return Ok(uri.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    Err(anyhow::anyhow!("No SPIFFE ID found in certificate"))
+}
+
+/// rustls `ClientCertVerifier` that authorizes a connecting client's SPIFFE
+/// ID *during* the handshake, after standard WebPKI chain validation
+struct SpiffeClientCertVerifier {
+    root_certs: rustls::RootCertStore,
+    authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeClientCertVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpiffeClientCertVerifier").finish_non_exhaustive()
+    }
+}
+
+impl ClientCertVerifier for SpiffeClientCertVerifier {
+    fn offer_client_auth(&self) -> bool {
+        true
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        true
+    }
+
+    fn root_hint_subjects(&self) -> &[rustls::DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let webpki_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(self.root_certs.clone()))
+            .build()
+            .map_err(|e| rustls::Error::General(format!("Failed to build client chain verifier: {}", e)))?;
+
+        webpki_verifier.verify_client_cert(end_entity, intermediates, now)?;
+
+        let spiffe_id = extract_spiffe_id_from_cert(end_entity)
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+
+        if !self.authorizer.authorize(&spiffe_id) {
+            return Err(rustls::Error::General(format!(
+                "SPIFFE ID not authorized: {}",
+                spiffe_id
+            )));
+        }
+
+        Ok(ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::server::WebPkiClientVerifier::builder(Arc::new(self.root_certs.clone()))
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::server::WebPkiClientVerifier::builder(Arc::new(self.root_certs.clone()))
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+        ]
+    }
+}
+
+/// Build the authorizer implied by the CLI flags: an exact match on
+/// `--allowed-client-spiffe-id` if given, else any identity within
+/// `--trust-domain`.
+fn build_authorizer(args: &Args) -> Arc<dyn SpiffeIdAuthorizer> {
+    match &args.allowed_client_spiffe_id {
+        Some(id) => Arc::new(AllowList(std::iter::once(id.clone()).collect())),
+        None => Arc::new(TrustDomainAuthorizer(args.trust_domain.clone())),
+    }
+}
+
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
 struct Args {
@@ -53,6 +246,11 @@ struct Args {
     /// Server SPIFFE ID
     #[arg(long, default_value = "spiffe://example.org/rust-server")]
     server_spiffe_id: String,
+
+    /// Exact SPIFFE ID a connecting client must present (if not set, any
+    /// identity within `trust_domain` is authorized)
+    #[arg(long)]
+    allowed_client_spiffe_id: Option<String>,
 }
 
 #[tokio::main]
@@ -107,26 +305,16 @@ async fn handle_client(
 
     info!("✓ mTLS handshake successful");
 
-    // Extract and verify SPIFFE ID from peer certificate
+    // The client's SPIFFE ID was already authorized during the handshake by
+    // `SpiffeClientCertVerifier`; this just logs which identity connected.
     let (_, server_connection) = tls_stream.get_ref();
     if let Some(certs) = server_connection.peer_certificates() {
-        info!("Peer presented {} certificate(s)", certs.len());
-
-        if !certs.is_empty() {
-            match verify_spiffe_certificate(&certs[0], &args.trust_domain) {
-                Ok(spiffe_id) => {
-                    info!("✓ Client SPIFFE ID verified: {}", spiffe_id);
-                    info!("✓ Client certificate verified");
-                }
-                Err(e) => {
-                    error!("✗ SPIFFE verification failed: {}", e);
-                    return Err(anyhow::anyhow!("SPIFFE verification failed: {}", e));
-                }
+        if let Some(leaf) = certs.first() {
+            match extract_spiffe_id_from_cert(leaf) {
+                Ok(spiffe_id) => info!("✓ Client SPIFFE ID: {}", spiffe_id),
+                Err(e) => warn!("Could not re-extract client SPIFFE ID for logging: {}", e),
             }
         }
-    } else {
-        warn!("No client certificates presented");
-        return Err(anyhow::anyhow!("No client certificates presented"));
     }
 
     // Simple echo server
@@ -302,12 +490,11 @@ fn create_server_config(
         info!("⚠ Go CA certificate not found");
     }
 
-    // Build server config with mTLS
-    let client_verifier = rustls::server::WebPkiClientVerifier::builder(
-        Arc::new(root_store),
-    )
-    .build()
-    .map_err(|e| anyhow::anyhow!("Failed to build client verifier: {:?}", e))?;
+    // Build server config with handshake-time SPIFFE authorization
+    let client_verifier = Arc::new(SpiffeClientCertVerifier {
+        root_certs: root_store,
+        authorizer: build_authorizer(args),
+    });
 
     let config = ServerConfig::builder()
         .with_client_cert_verifier(client_verifier)
@@ -334,59 +521,3 @@ fn save_certs(
     Ok(())
 }
 
-/// Verify SPIFFE certificate and extract SPIFFE ID
-fn verify_spiffe_certificate(cert_der: &CertificateDer, expected_trust_domain: &str) -> Result<String> {
-    // Parse the certificate
-    let cert_bytes = cert_der.as_ref();
-    let (_, cert) = X509Certificate::from_der(cert_bytes)
-        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
-
-    // Extract SPIFFE ID from SAN (Subject Alternative Name)
-    let mut spiffe_id = None;
-
-    for ext in cert.extensions() {
-        if ext.oid == x509_parser::oid_registry::OID_X509_EXT_SUBJECT_ALT_NAME {
-            if let ParsedExtension::SubjectAlternativeName(san) = &ext.parsed_extension() {
-                for name in &san.general_names {
-                    if let GeneralName::URI(uri) = name {
-                        if uri.starts_with("spiffe://") {
-                            spiffe_id = Some(uri.to_string());
-                            break;
-                        }
-                    }
-                }
-            }
-            break;
-        }
-    }
-
-    let spiffe_id = spiffe_id.ok_or_else(|| anyhow::anyhow!("No SPIFFE ID found in certificate"))?;
-
-    // Validate SPIFFE ID format
-    if !spiffe_id.starts_with("spiffe://") {
-        return Err(anyhow::anyhow!("Invalid SPIFFE ID format: {}", spiffe_id));
-    }
-
-    // Extract trust domain from SPIFFE ID
-    let spiffe_parts: Vec<&str> = spiffe_id.strip_prefix("spiffe://").unwrap().split('/').collect();
-    if spiffe_parts.is_empty() {
-        return Err(anyhow::anyhow!("Invalid SPIFFE ID: missing trust domain"));
-    }
-
-    let trust_domain = spiffe_parts[0];
-
-    // Verify trust domain matches expected
-    if trust_domain != expected_trust_domain {
-        return Err(anyhow::anyhow!(
-            "Trust domain mismatch: expected '{}', found '{}'",
-            expected_trust_domain,
-            trust_domain
-        ));
-    }
-
-    info!("✓ SPIFFE ID validation passed");
-    info!("  - SPIFFE ID: {}", spiffe_id);
-    info!("  - Trust Domain: {}", trust_domain);
-
-    Ok(spiffe_id)
-}
@@ -0,0 +1,180 @@
+//! Federation bundle-endpoint client
+//!
+//! Turns a [`TrustBundleStore`] entry for a foreign trust domain from a
+//! static container into a live SPIFFE federation client: polls the remote
+//! domain's SPIFFE bundle endpoint over HTTPS, only ever advances the store
+//! when the fetched bundle's `spiffe_sequence` is newer, and notifies
+//! subscribers so TLS configuration can be reloaded on change.
+
+use crate::error::{Error, Result};
+use crate::trust_bundle::{TrustBundle, TrustBundleStore};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// Fallback poll interval used when a bundle carries no usable expiry
+const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Never poll more often than this, even if a bundle is already expired
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Polls a remote trust domain's SPIFFE bundle endpoint and keeps a local
+/// [`TrustBundleStore`] in sync with it
+pub struct BundleEndpoint {
+    trust_domain: String,
+    url: String,
+    store: TrustBundleStore,
+    changed: watch::Sender<TrustBundle>,
+}
+
+impl BundleEndpoint {
+    /// Create a new endpoint client
+    ///
+    /// `url` is the HTTPS SPIFFE bundle endpoint for `trust_domain`.
+    /// `initial` seeds both the store and the watch channel; pass the
+    /// bundle already known for this domain (or an empty placeholder if
+    /// none has been fetched yet).
+    pub fn new(trust_domain: String, url: String, store: TrustBundleStore, initial: TrustBundle) -> Self {
+        let (changed, _) = watch::channel(initial);
+
+        BundleEndpoint {
+            trust_domain,
+            url,
+            store,
+            changed,
+        }
+    }
+
+    /// Subscribe to bundle updates for this trust domain
+    ///
+    /// Fires every time [`Self::refresh_once`] advances the bundle, so
+    /// `MtlsConfig`/verifiers can reload roots in response.
+    pub fn subscribe(&self) -> watch::Receiver<TrustBundle> {
+        self.changed.subscribe()
+    }
+
+    /// Fetch the bundle once and update the store if its sequence advanced
+    ///
+    /// Returns `true` if the store was updated.
+    pub async fn refresh_once(&self) -> Result<bool> {
+        let body = self.fetch_bundle_document().await?;
+        let bundle = TrustBundle::from_spiffe_bundle_json(self.trust_domain.clone(), &body)?;
+
+        let updated = self.store.update_if_newer(bundle.clone())?;
+        if updated {
+            info!(
+                "Federated trust bundle for {} advanced to sequence {}",
+                self.trust_domain,
+                bundle.sequence_number()
+            );
+            let _ = self.changed.send(bundle);
+        } else {
+            debug!(
+                "Fetched federated bundle for {} was not newer than the current one",
+                self.trust_domain
+            );
+        }
+
+        Ok(updated)
+    }
+
+    /// Run the background refresh loop forever
+    ///
+    /// After each poll, the next one is scheduled using the freshly stored
+    /// bundle's [`TrustBundle::earliest_expiry`], so a domain whose roots
+    /// are about to expire gets polled again well before that happens;
+    /// falls back to `DEFAULT_REFRESH_INTERVAL` when the bundle carries no
+    /// usable expiry, and never polls more often than `MIN_REFRESH_INTERVAL`.
+    pub async fn run(self: Arc<Self>) {
+        loop {
+            if let Err(e) = self.refresh_once().await {
+                warn!("Failed to refresh federated bundle for {}: {}", self.trust_domain, e);
+            }
+
+            // Poll again halfway to the earliest root expiry; a bundle whose
+            // roots have already expired is polled again after the minimum.
+            let next_delay = match self
+                .store
+                .get_bundle(&self.trust_domain)
+                .and_then(|bundle| bundle.earliest_expiry())
+            {
+                Some(expiry) => (expiry - chrono::Utc::now())
+                    .to_std()
+                    .map(|remaining| remaining / 2)
+                    .unwrap_or(MIN_REFRESH_INTERVAL),
+                None => DEFAULT_REFRESH_INTERVAL,
+            }
+            .max(MIN_REFRESH_INTERVAL);
+
+            debug!("Next federation poll for {} in {:?}", self.trust_domain, next_delay);
+            tokio::time::sleep(next_delay).await;
+        }
+    }
+
+    /// Fetch the raw SPIFFE bundle JSON document over HTTPS
+    ///
+    /// Uses the crate's existing rustls stack with the standard web PKI
+    /// roots (not the workload's own SPIFFE trust bundle, since the bundle
+    /// endpoint is a regular HTTPS server, not an SVID peer).
+    async fn fetch_bundle_document(&self) -> Result<String> {
+        let url = url::Url::parse(&self.url)
+            .map_err(|e| Error::agent_error(format!("Invalid bundle endpoint URL: {}", e)))?;
+
+        let host = url
+            .host_str()
+            .ok_or_else(|| Error::agent_error("Bundle endpoint URL has no host"))?
+            .to_string();
+        let port = url.port_or_known_default().unwrap_or(443);
+        let path_and_query = match url.query() {
+            Some(q) => format!("{}?{}", url.path(), q),
+            None => url.path().to_string(),
+        };
+
+        let tcp = tokio::net::TcpStream::connect((host.as_str(), port))
+            .await
+            .map_err(|e| Error::agent_error(format!("Failed to connect to bundle endpoint: {}", e)))?;
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls_config = Arc::new(
+            rustls::ClientConfig::builder()
+                .with_root_certificates(roots)
+                .with_no_client_auth(),
+        );
+
+        let connector = tokio_rustls::TlsConnector::from(tls_config);
+        let server_name = rustls::pki_types::ServerName::try_from(host.clone())
+            .map_err(|e| Error::agent_error(format!("Invalid bundle endpoint host: {}", e)))?
+            .to_owned();
+
+        let mut tls_stream = connector
+            .connect(server_name, tcp)
+            .await
+            .map_err(|e| Error::agent_error(format!("TLS handshake with bundle endpoint failed: {}", e)))?;
+
+        let request = format!(
+            "GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nAccept: application/json\r\n\r\n",
+            path_and_query, host
+        );
+        tls_stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| Error::agent_error(format!("Failed to send bundle endpoint request: {}", e)))?;
+
+        let mut response = Vec::new();
+        tls_stream
+            .read_to_end(&mut response)
+            .await
+            .map_err(|e| Error::agent_error(format!("Failed to read bundle endpoint response: {}", e)))?;
+
+        let response = String::from_utf8_lossy(&response).into_owned();
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body)
+            .ok_or_else(|| Error::agent_error("Malformed HTTP response from bundle endpoint"))?;
+
+        Ok(body.to_string())
+    }
+}
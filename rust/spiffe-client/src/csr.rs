@@ -0,0 +1,72 @@
+//! Keypair and CSR generation for requesting or rotating X.509 SVIDs
+//!
+//! Workloads that mint their own identity (rather than only consuming an
+//! SVID handed to them by the agent) need to produce a fresh ECDSA P-256
+//! keypair and a PKCS#10 certificate signing request with their SPIFFE ID
+//! embedded as a URI SAN, then submit the CSR to SPIRE's signing APIs.
+
+use crate::error::{Error, Result};
+use crate::spiffe_id::SpiffeId;
+
+/// A freshly generated keypair and the CSR built from it
+///
+/// `der` is the DER-encoded PKCS#10 certificate signing request, ready to be
+/// submitted over the Workload or Delegated Identity API. `private_key_der`
+/// is the PKCS#8 DER-encoded private key and must be kept alongside the
+/// X.509 chain returned by the signing authority.
+pub struct SvidCsr {
+    der: Vec<u8>,
+    private_key_der: Vec<u8>,
+}
+
+impl SvidCsr {
+    /// Generate a new ECDSA P-256 keypair and a CSR embedding `spiffe_id` as
+    /// a URI SAN
+    pub fn generate(spiffe_id: &SpiffeId) -> Result<Self> {
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256)
+            .map_err(|e| Error::tls_error(format!("Failed to generate SVID keypair: {}", e)))?;
+
+        let mut params = rcgen::CertificateParams::default();
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        params.subject_alt_names = vec![rcgen::SanType::URI(
+            rcgen::Ia5String::try_from(spiffe_id.to_string())
+                .map_err(|e| Error::invalid_spiffe_id(format!("SPIFFE ID is not IA5String-safe: {}", e)))?,
+        )];
+
+        let csr = params
+            .serialize_request(&key_pair)
+            .map_err(|e| Error::tls_error(format!("Failed to build CSR: {}", e)))?;
+
+        Ok(Self {
+            der: csr.der().to_vec(),
+            private_key_der: key_pair.serialize_der(),
+        })
+    }
+
+    /// The DER-encoded PKCS#10 certificate signing request
+    pub fn der(&self) -> &[u8] {
+        &self.der
+    }
+
+    /// The PKCS#8 DER-encoded private key paired with this CSR
+    pub fn private_key_der(&self) -> &[u8] {
+        &self.private_key_der
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_csr_embeds_spiffe_id() {
+        let id = SpiffeId::new("example.org", "/service/web").unwrap();
+        let csr = SvidCsr::generate(&id).unwrap();
+
+        assert!(!csr.der().is_empty());
+        assert!(!csr.private_key_der().is_empty());
+
+        let uri = id.to_string();
+        assert!(csr.der().windows(uri.len()).any(|w| w == uri.as_bytes()));
+    }
+}
@@ -5,19 +5,33 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+/// Generated SPIFFE Workload API gRPC types
+#[allow(missing_docs)]
+pub mod proto {
+    tonic::include_proto!("spiffe.workload");
+}
+
 pub mod error;
 pub mod spiffe_id;
 pub mod svid;
 pub mod trust_bundle;
 pub mod workload;
+pub mod svid_manager;
 pub mod mtls;
+pub mod csr;
+pub mod bundle_endpoint;
+pub mod jwt;
 
 pub use error::{Error, Result};
-pub use spiffe_id::SpiffeId;
+pub use spiffe_id::{SpiffeId, SpiffeIdMatcher};
 pub use svid::{X509Svid, JwtSvid};
 pub use trust_bundle::TrustBundle;
-pub use workload::{WorkloadApiClient, WorkloadApiConfig, ManagedWorkloadClient};
+pub use workload::{WorkloadApiClient, WorkloadApiConfig, ManagedWorkloadClient, BackoffConfig};
+pub use svid_manager::{SvidManager, RotationPolicy};
 pub use mtls::MtlsConfig;
+pub use csr::SvidCsr;
+pub use bundle_endpoint::BundleEndpoint;
+pub use jwt::{SpiffeJwtValidator, Claims as JwtClaims};
 
 #[cfg(test)]
 mod tests {
@@ -3,10 +3,431 @@
 use crate::error::{Error, Result};
 use crate::svid::X509Svid;
 use crate::trust_bundle::TrustBundle;
-use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use arc_swap::ArcSwap;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::ResolvesClientCert;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::{DigitallySignedStruct, DistinguishedName, SignatureScheme};
 use std::sync::Arc;
 use tokio_rustls::rustls::{self, ClientConfig, ServerConfig};
 use tracing::{debug, info, warn};
+use webpki::{EndEntityCert, KeyUsage};
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Validate `end_entity`'s chain against `roots`, with no hostname/SAN check
+///
+/// SPIFFE SVIDs carry only a URI SAN, never a DNS/IP SAN, so
+/// `rustls::client::WebPkiServerVerifier` (which always checks the leaf
+/// against a `ServerName`) can never accept one — there is no name to match.
+/// This builds the `webpki` end-entity cert directly and calls
+/// `verify_for_usage` with no server name, leaving identity binding to the
+/// caller's own SPIFFE ID check.
+fn verify_chain_for_server_auth(
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    roots: &rustls::RootCertStore,
+    now: UnixTime,
+) -> Result<(), rustls::Error> {
+    let cert = EndEntityCert::try_from(end_entity)
+        .map_err(|e| rustls::Error::General(format!("Invalid end-entity certificate: {:?}", e)))?;
+
+    let sig_algs = rustls::crypto::ring::default_provider().signature_verification_algorithms;
+
+    cert.verify_for_usage(
+        sig_algs.all,
+        &roots.roots,
+        intermediates,
+        now,
+        KeyUsage::server_auth(),
+        None,
+    )
+    .map(|_| ())
+    .map_err(|e| rustls::Error::General(format!("Certificate chain validation failed: {:?}", e)))
+}
+
+/// Holds the current SVID's signing key behind an `ArcSwap`
+///
+/// Rotation (see [`MtlsConfig::update_svid`]) just swaps the pointer here
+/// instead of rebuilding the `ClientConfig`/`ServerConfig`, so connectors
+/// and acceptors created once stay valid and automatically present the
+/// latest SVID on every subsequent handshake.
+pub struct RotatableIdentity {
+    current: ArcSwap<CertifiedKey>,
+}
+
+impl RotatableIdentity {
+    /// Wrap an initial `CertifiedKey`
+    pub fn new(key: CertifiedKey) -> Self {
+        RotatableIdentity {
+            current: ArcSwap::from_pointee(key),
+        }
+    }
+
+    /// Swap in a newly rotated `CertifiedKey`
+    pub fn store(&self, key: CertifiedKey) {
+        self.current.store(Arc::new(key));
+    }
+
+    /// The currently active `CertifiedKey`
+    pub fn current(&self) -> Arc<CertifiedKey> {
+        self.current.load_full()
+    }
+}
+
+impl std::fmt::Debug for RotatableIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RotatableIdentity").finish_non_exhaustive()
+    }
+}
+
+/// Holds the current set of trust anchors behind an `ArcSwap`
+///
+/// Rotation (see [`MtlsConfig::update_bundle`]) just swaps the pointer here
+/// instead of rebuilding the `ClientConfig`/`ServerConfig`, so a handshake
+/// already in flight finishes against the roots it started with while the
+/// next one picks up newly federated trust bundles immediately.
+pub struct SwappableRootStore {
+    current: ArcSwap<rustls::RootCertStore>,
+}
+
+impl SwappableRootStore {
+    /// Wrap an initial `RootCertStore`
+    pub fn new(store: rustls::RootCertStore) -> Self {
+        SwappableRootStore {
+            current: ArcSwap::from_pointee(store),
+        }
+    }
+
+    /// Swap in a newly rotated `RootCertStore`
+    pub fn store(&self, store: rustls::RootCertStore) {
+        self.current.store(Arc::new(store));
+    }
+
+    /// The currently active `RootCertStore`
+    pub fn current(&self) -> Arc<rustls::RootCertStore> {
+        self.current.load_full()
+    }
+}
+
+impl std::fmt::Debug for SwappableRootStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SwappableRootStore").finish_non_exhaustive()
+    }
+}
+
+impl ResolvesClientCert for RotatableIdentity {
+    fn resolve(
+        &self,
+        _root_hint_subjects: &[&[u8]],
+        _sigschemes: &[SignatureScheme],
+    ) -> Option<Arc<CertifiedKey>> {
+        Some(self.current())
+    }
+
+    fn has_certs(&self) -> bool {
+        true
+    }
+}
+
+impl ResolvesServerCert for RotatableIdentity {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(self.current())
+    }
+}
+
+/// Selects among several SPIFFE identities by the ClientHello's SNI, so one
+/// listener can host multiple SPIFFE identities / virtual services
+///
+/// Each identity is independently rotatable through its own
+/// [`RotatableIdentity`]. A ClientHello with no SNI, or an SNI that matches
+/// none of the configured hostnames, falls back to `default`.
+pub struct SniIdentityResolver {
+    by_hostname: std::collections::HashMap<String, Arc<RotatableIdentity>>,
+    default: Arc<RotatableIdentity>,
+}
+
+impl SniIdentityResolver {
+    /// Create a resolver that falls back to `default` on no SNI match
+    pub fn new(default: Arc<RotatableIdentity>) -> Self {
+        SniIdentityResolver {
+            by_hostname: std::collections::HashMap::new(),
+            default,
+        }
+    }
+
+    /// Route the given SNI hostname to `identity`
+    pub fn add_identity(&mut self, hostname: impl Into<String>, identity: Arc<RotatableIdentity>) {
+        self.by_hostname.insert(hostname.into(), identity);
+    }
+}
+
+impl std::fmt::Debug for SniIdentityResolver {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SniIdentityResolver")
+            .field("hostnames", &self.by_hostname.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl ResolvesServerCert for SniIdentityResolver {
+    fn resolve(&self, client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        let identity = client_hello
+            .server_name()
+            .and_then(|name| self.by_hostname.get(name))
+            .unwrap_or(&self.default);
+
+        Some(identity.current())
+    }
+}
+
+/// Extract the single `spiffe://` URI SAN from a DER-encoded leaf certificate
+///
+/// Per the SPIFFE X.509-SVID spec there must be exactly one URI SAN;
+/// certificates with zero or multiple are rejected.
+fn extract_spiffe_id_from_der(cert_der: &[u8]) -> Result<crate::SpiffeId> {
+    let (_, cert) = X509Certificate::from_der(cert_der)
+        .map_err(|e| Error::ValidationError(format!("Failed to parse certificate: {}", e)))?;
+
+    let mut spiffe_uris = Vec::new();
+    for ext in cert.extensions() {
+        if let x509_parser::extensions::ParsedExtension::SubjectAlternativeName(san) = ext.parsed_extension() {
+            for name in &san.general_names {
+                if let GeneralName::URI(uri) = name {
+                    if uri.starts_with("spiffe://") {
+                        spiffe_uris.push(*uri);
+                    }
+                }
+            }
+        }
+    }
+
+    match spiffe_uris.len() {
+        0 => Err(Error::ValidationError("Certificate has no spiffe:// URI SAN".into())),
+        1 => crate::SpiffeId::parse(spiffe_uris[0]),
+        n => Err(Error::ValidationError(format!(
+            "Certificate has {} spiffe:// URI SANs, expected exactly one",
+            n
+        ))),
+    }
+}
+
+/// Policy deciding whether a peer's SPIFFE ID is allowed to connect
+///
+/// Implementations plug into [`SpiffeServerCertVerifier`] and
+/// [`SpiffeClientCertVerifier`] so callers can restrict connections to a
+/// single ID, a set of IDs, or any identity within a trust domain.
+pub trait SpiffeIdAuthorizer: std::fmt::Debug + Send + Sync {
+    /// Returns `true` if `id` is allowed to connect
+    fn authorize(&self, id: &crate::SpiffeId) -> bool;
+}
+
+/// Authorize any peer presenting a valid SPIFFE ID
+#[derive(Debug, Default)]
+pub struct AuthorizeAny;
+
+impl SpiffeIdAuthorizer for AuthorizeAny {
+    fn authorize(&self, _id: &crate::SpiffeId) -> bool {
+        true
+    }
+}
+
+/// Authorize only peers whose ID exactly matches the given one
+#[derive(Debug)]
+pub struct AuthorizeId(pub crate::SpiffeId);
+
+impl SpiffeIdAuthorizer for AuthorizeId {
+    fn authorize(&self, id: &crate::SpiffeId) -> bool {
+        id == &self.0
+    }
+}
+
+/// Authorize peers whose ID is one of the given set
+#[derive(Debug)]
+pub struct AuthorizeOneOf(pub Vec<crate::SpiffeId>);
+
+impl SpiffeIdAuthorizer for AuthorizeOneOf {
+    fn authorize(&self, id: &crate::SpiffeId) -> bool {
+        self.0.contains(id)
+    }
+}
+
+/// Authorize any peer belonging to the given trust domain
+#[derive(Debug)]
+pub struct AuthorizeMemberOf(pub String);
+
+impl SpiffeIdAuthorizer for AuthorizeMemberOf {
+    fn authorize(&self, id: &crate::SpiffeId) -> bool {
+        id.is_member_of(&self.0)
+    }
+}
+
+/// rustls `ServerCertVerifier` that authorizes the server's SPIFFE ID after
+/// standard WebPKI chain validation against the trust bundle
+///
+/// SPIFFE SVIDs carry no DNS name, so the usual hostname check is replaced
+/// with a caller-supplied [`SpiffeIdAuthorizer`] applied to the URI SAN.
+pub struct SpiffeServerCertVerifier {
+    roots: Arc<SwappableRootStore>,
+    authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeServerCertVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpiffeServerCertVerifier").finish_non_exhaustive()
+    }
+}
+
+impl ServerCertVerifier for SpiffeServerCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        // Chain validation only, with no hostname/SAN check; SPIFFE SVIDs
+        // carry no DNS name, so identity binding happens below.
+        let _ = (server_name, ocsp_response);
+        verify_chain_for_server_auth(end_entity, intermediates, &self.roots.current(), now)?;
+
+        let spiffe_id = extract_spiffe_id_from_der(end_entity.as_ref())
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+
+        if !self.authorizer.authorize(&spiffe_id) {
+            return Err(rustls::Error::General(format!(
+                "SPIFFE ID not authorized: {}",
+                spiffe_id
+            )));
+        }
+
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::client::WebPkiServerVerifier::builder(self.roots.current())
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::client::WebPkiServerVerifier::builder(self.roots.current())
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+        ]
+    }
+}
+
+/// rustls `ClientCertVerifier` that authorizes a connecting client's SPIFFE
+/// ID after standard WebPKI chain validation against the trust bundle
+pub struct SpiffeClientCertVerifier {
+    roots: Arc<SwappableRootStore>,
+    authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeClientCertVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpiffeClientCertVerifier").finish_non_exhaustive()
+    }
+}
+
+impl ClientCertVerifier for SpiffeClientCertVerifier {
+    fn offer_client_auth(&self) -> bool {
+        true
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        true
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let webpki_verifier = rustls::server::WebPkiClientVerifier::builder(self.roots.current())
+            .build()
+            .map_err(|e| rustls::Error::General(format!("Failed to build client chain verifier: {}", e)))?;
+
+        webpki_verifier.verify_client_cert(end_entity, intermediates, now)?;
+
+        let spiffe_id = extract_spiffe_id_from_der(end_entity.as_ref())
+            .map_err(|e| rustls::Error::General(e.to_string()))?;
+
+        if !self.authorizer.authorize(&spiffe_id) {
+            return Err(rustls::Error::General(format!(
+                "SPIFFE ID not authorized: {}",
+                spiffe_id
+            )));
+        }
+
+        Ok(ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::server::WebPkiClientVerifier::builder(self.roots.current())
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::server::WebPkiClientVerifier::builder(self.roots.current())
+            .build()
+            .map_err(|e| rustls::Error::General(e.to_string()))?
+            .verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        vec![
+            SignatureScheme::RSA_PSS_SHA256,
+            SignatureScheme::RSA_PSS_SHA384,
+            SignatureScheme::RSA_PSS_SHA512,
+            SignatureScheme::ECDSA_NISTP256_SHA256,
+            SignatureScheme::ECDSA_NISTP384_SHA384,
+        ]
+    }
+}
 
 /// mTLS configuration for SPIFFE-compliant connections
 #[derive(Clone)]
@@ -17,12 +438,23 @@ pub struct MtlsConfig {
     server_config: Option<Arc<ServerConfig>>,
     /// Associated SPIFFE ID
     spiffe_id: crate::SpiffeId,
+    /// Current signing identity, swapped in place on rotation so that
+    /// `client_config`/`server_config` never need to be rebuilt
+    identity: Arc<RotatableIdentity>,
+    /// Current trust anchors, swapped in place when federated bundles update
+    roots: Arc<SwappableRootStore>,
 }
 
 impl MtlsConfig {
     /// Create mTLS configuration from X.509 SVID and trust bundle
+    ///
+    /// Verification still runs through the custom SPIFFE-aware verifier
+    /// (chain validation plus a SAN-based SPIFFE ID check, not WebPKI's
+    /// hostname-based verification), but with an [`AuthorizeAny`] policy
+    /// that accepts any peer presenting a well-formed SPIFFE ID. Use
+    /// [`Self::from_svid_with_authorizer`] to enforce a tighter SPIFFE ID
+    /// policy during the handshake itself.
     pub fn from_svid(svid: &X509Svid, trust_bundle: &TrustBundle) -> Result<Self> {
-        // Validate SVID before use
         svid.validate()?;
 
         info!(
@@ -30,81 +462,135 @@ impl MtlsConfig {
             svid.spiffe_id()
         );
 
+        let identity = Arc::new(RotatableIdentity::new(Self::build_certified_key(svid)?));
+        let roots = Arc::new(SwappableRootStore::new(Self::build_root_store(trust_bundle)?));
+
         // Build client configuration
-        let client_config = Self::build_client_config(svid, trust_bundle)?;
+        let client_config = Self::build_client_config(roots.clone(), identity.clone())?;
 
         // Build server configuration (for accepting connections)
-        let server_config = Self::build_server_config(svid, trust_bundle)?;
+        let server_config = Self::build_server_config(roots.clone(), identity.clone())?;
 
         Ok(MtlsConfig {
             client_config: Arc::new(client_config),
             server_config: Some(Arc::new(server_config)),
             spiffe_id: svid.spiffe_id().clone(),
+            identity,
+            roots,
         })
     }
 
-    /// Build client configuration for outbound mTLS connections
-    fn build_client_config(
+    /// Create mTLS configuration that only accepts peers authorized by `authorizer`
+    ///
+    /// Like [`Self::from_svid`], this binds both the client and server
+    /// configuration to the custom SPIFFE-aware verifier, but with
+    /// `authorizer` in place of [`AuthorizeAny`] so that only peers whose
+    /// SPIFFE ID satisfies it are accepted.
+    pub fn from_svid_with_authorizer(
         svid: &X509Svid,
         trust_bundle: &TrustBundle,
-    ) -> Result<ClientConfig> {
-        let mut root_store = rustls::RootCertStore::empty();
+        authorizer: Arc<dyn SpiffeIdAuthorizer>,
+    ) -> Result<Self> {
+        svid.validate()?;
+
+        info!(
+            "Creating SPIFFE-authorized mTLS config for SPIFFE ID: {}",
+            svid.spiffe_id()
+        );
+
+        let identity = Arc::new(RotatableIdentity::new(Self::build_certified_key(svid)?));
+        let roots = Arc::new(SwappableRootStore::new(Self::build_root_store(trust_bundle)?));
 
-        // Add trust bundle certificates
+        let client_config = Self::build_client_config_with_authorizer(roots.clone(), identity.clone(), authorizer.clone())?;
+        let server_config = Self::build_server_config_with_authorizer(roots.clone(), identity.clone(), authorizer)?;
+
+        Ok(MtlsConfig {
+            client_config: Arc::new(client_config),
+            server_config: Some(Arc::new(server_config)),
+            spiffe_id: svid.spiffe_id().clone(),
+            identity,
+            roots,
+        })
+    }
+
+    /// Build a `RootCertStore` from a trust bundle's anchor certificates
+    fn build_root_store(trust_bundle: &TrustBundle) -> Result<rustls::RootCertStore> {
+        let mut root_store = rustls::RootCertStore::empty();
         for cert_der in trust_bundle.certificates() {
-            let cert = CertificateDer::from(cert_der.clone());
-            root_store.add(cert).map_err(|e| {
-                Error::tls_error(format!("Failed to add root certificate: {}", e))
-            })?;
+            root_store
+                .add(CertificateDer::from(cert_der.clone()))
+                .map_err(|e| Error::tls_error(format!("Failed to add root certificate: {}", e)))?;
         }
+        Ok(root_store)
+    }
+
+    /// Build the rustls signing key (cert chain + private key) for an SVID
+    fn build_certified_key(svid: &X509Svid) -> Result<CertifiedKey> {
+        let cert_chain = Self::convert_cert_chain(svid.cert_chain())?;
+        let private_key = Self::convert_private_key(svid.private_key())?;
+
+        let signing_key = rustls::crypto::ring::sign::any_supported_type(&private_key)
+            .map_err(|e| Error::tls_error(format!("Unsupported SVID private key type: {}", e)))?;
+
+        Ok(CertifiedKey::new(cert_chain, signing_key))
+    }
 
+    /// Build client configuration for outbound mTLS connections
+    ///
+    /// Uses a cert resolver backed by `identity` and a verifier backed by
+    /// `roots` rather than a fixed `with_root_certificates`, so the config
+    /// stays valid across both identity and trust bundle rotations.
+    fn build_client_config(
+        roots: Arc<SwappableRootStore>,
+        identity: Arc<RotatableIdentity>,
+    ) -> Result<ClientConfig> {
         debug!(
             "Added {} certificates to root store",
-            root_store.len()
+            roots.current().len()
         );
 
-        // Create TLS 1.2+ configuration with strong ciphers
-        let config = ClientConfig::builder()
-            .with_root_certificates(root_store)
-            .with_client_auth_cert(
-                Self::convert_cert_chain(svid.cert_chain())?,
-                Self::convert_private_key(svid.private_key())?,
-            )
-            .map_err(|e| Error::tls_error(format!("Failed to create client config: {}", e)))?;
-
-        Ok(config)
+        Self::build_client_config_with_authorizer(roots, identity, Arc::new(AuthorizeAny))
     }
 
     /// Build server configuration for accepting mTLS connections
+    ///
+    /// Uses a cert resolver backed by `identity` and a verifier backed by
+    /// `roots` rather than a fixed `with_client_cert_verifier`, so the
+    /// config stays valid across both identity and trust bundle rotations.
     fn build_server_config(
-        svid: &X509Svid,
-        trust_bundle: &TrustBundle,
+        roots: Arc<SwappableRootStore>,
+        identity: Arc<RotatableIdentity>,
     ) -> Result<ServerConfig> {
-        let mut root_store = rustls::RootCertStore::empty();
+        Self::build_server_config_with_authorizer(roots, identity, Arc::new(AuthorizeAny))
+    }
 
-        // Add trust bundle for client verification
-        for cert_der in trust_bundle.certificates() {
-            let cert = CertificateDer::from(cert_der.clone());
-            root_store.add(cert).map_err(|e| {
-                Error::tls_error(format!("Failed to add root certificate: {}", e))
-            })?;
-        }
+    /// Build a client configuration that authorizes the server's SPIFFE ID
+    fn build_client_config_with_authorizer(
+        roots: Arc<SwappableRootStore>,
+        identity: Arc<RotatableIdentity>,
+        authorizer: Arc<dyn SpiffeIdAuthorizer>,
+    ) -> Result<ClientConfig> {
+        let verifier = Arc::new(SpiffeServerCertVerifier { roots, authorizer });
+
+        let config = ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_client_cert_resolver(identity);
 
-        // Create client certificate verifier
-        let client_cert_verifier = rustls::server::WebPkiClientVerifier::builder(
-            Arc::new(root_store),
-        )
-        .build()
-        .map_err(|e| Error::tls_error(format!("Failed to create client verifier: {}", e)))?;
+        Ok(config)
+    }
+
+    /// Build a server configuration that authorizes the client's SPIFFE ID
+    fn build_server_config_with_authorizer(
+        roots: Arc<SwappableRootStore>,
+        identity: Arc<RotatableIdentity>,
+        authorizer: Arc<dyn SpiffeIdAuthorizer>,
+    ) -> Result<ServerConfig> {
+        let verifier = Arc::new(SpiffeClientCertVerifier { roots, authorizer });
 
-        // Create server configuration requiring client certificates
         let config = ServerConfig::builder()
-            .with_client_cert_verifier(client_cert_verifier)
-            .with_single_cert(
-                Self::convert_cert_chain(svid.cert_chain())?,
-                Self::convert_private_key(svid.private_key())?,
-            )
-            .map_err(|e| Error::tls_error(format!("Failed to create server config: {}", e)))?;
+            .with_client_cert_verifier(verifier)
+            .with_cert_resolver(identity);
 
         Ok(config)
     }
@@ -149,6 +635,20 @@ impl MtlsConfig {
         &self.spiffe_id
     }
 
+    /// Set the ALPN protocols to negotiate, in preference order
+    ///
+    /// Applied to both `client_config` and `server_config` (e.g.
+    /// `vec![b"h2".to_vec(), b"http/1.1".to_vec()]`), so a single SPIFFE
+    /// listener can negotiate application protocols the same way a
+    /// conventional TLS server would.
+    pub fn with_alpn_protocols(mut self, protocols: Vec<Vec<u8>>) -> Self {
+        Arc::make_mut(&mut self.client_config).alpn_protocols = protocols.clone();
+        if let Some(server_config) = self.server_config.as_mut() {
+            Arc::make_mut(server_config).alpn_protocols = protocols;
+        }
+        self
+    }
+
     /// Create a TLS connector for client connections
     pub fn connector(&self) -> tokio_rustls::TlsConnector {
         tokio_rustls::TlsConnector::from(self.client_config.clone())
@@ -162,6 +662,50 @@ impl MtlsConfig {
             .ok_or_else(|| Error::tls_error("Server configuration not available"))
     }
 
+    /// Build a `quinn::ClientConfig` for outbound QUIC connections
+    ///
+    /// Reuses `client_config`'s SPIFFE-aware verifier and trust bundle, so
+    /// peer SPIFFE IDs are authenticated on QUIC handshakes exactly as they
+    /// are on TCP mTLS.
+    pub fn quic_client_config(&self) -> Result<quinn::ClientConfig> {
+        let crypto = quinn::crypto::rustls::QuicClientConfig::try_from((*self.client_config).clone())
+            .map_err(|e| Error::tls_error(format!("Failed to build QUIC client crypto config: {}", e)))?;
+
+        Ok(quinn::ClientConfig::new(Arc::new(crypto)))
+    }
+
+    /// Build a `quinn::ServerConfig` for accepting QUIC connections
+    ///
+    /// Reuses `server_config`'s SPIFFE-aware verifier and trust bundle, the
+    /// same as [`Self::quic_client_config`] does for outbound connections.
+    pub fn quic_server_config(&self) -> Result<quinn::ServerConfig> {
+        let server_config = self
+            .server_config
+            .as_ref()
+            .ok_or_else(|| Error::tls_error("Server configuration not available"))?;
+
+        let crypto = quinn::crypto::rustls::QuicServerConfig::try_from((**server_config).clone())
+            .map_err(|e| Error::tls_error(format!("Failed to build QUIC server crypto config: {}", e)))?;
+
+        Ok(quinn::ServerConfig::with_crypto(Arc::new(crypto)))
+    }
+
+    /// Build a `quinn::Endpoint` bound to `addr` for mutually-authenticated
+    /// SPIFFE QUIC/HTTP3 traffic
+    ///
+    /// The endpoint accepts inbound connections using [`Self::quic_server_config`]
+    /// and is pre-configured to dial out using [`Self::quic_client_config`],
+    /// so the same identity and trust bundle cover both directions.
+    pub fn quic_endpoint(&self, addr: std::net::SocketAddr) -> Result<quinn::Endpoint> {
+        let server_config = self.quic_server_config()?;
+
+        let mut endpoint = quinn::Endpoint::server(server_config, addr)
+            .map_err(|e| Error::tls_error(format!("Failed to bind QUIC endpoint: {}", e)))?;
+        endpoint.set_default_client_config(self.quic_client_config()?);
+
+        Ok(endpoint)
+    }
+
     /// Verify that the configuration supports required TLS versions
     pub fn verify_tls_version(&self) -> Result<()> {
         // This is validated during construction, but can be re-checked
@@ -170,7 +714,13 @@ impl MtlsConfig {
     }
 
     /// Update the configuration with a new SVID (for rotation)
-    pub fn update_svid(&mut self, svid: &X509Svid, trust_bundle: &TrustBundle) -> Result<()> {
+    ///
+    /// Rather than rebuilding `client_config`/`server_config`, this swaps
+    /// the signing key behind [`RotatableIdentity`] in place; any connector
+    /// or acceptor created from this `MtlsConfig` earlier picks up the new
+    /// SVID on its very next handshake. The trust bundle's root store is
+    /// unaffected and is not re-validated here.
+    pub fn update_svid(&mut self, svid: &X509Svid, _trust_bundle: &TrustBundle) -> Result<()> {
         if svid.is_expired() {
             return Err(Error::ValidationError(
                 "Cannot update with expired SVID".into(),
@@ -178,19 +728,34 @@ impl MtlsConfig {
         }
 
         info!(
-            "Updating mTLS config with new SVID for {}",
+            "Rotating mTLS identity to new SVID for {}",
             svid.spiffe_id()
         );
 
-        let client_config = Self::build_client_config(svid, trust_bundle)?;
-        let server_config = Self::build_server_config(svid, trust_bundle)?;
-
-        self.client_config = Arc::new(client_config);
-        self.server_config = Some(Arc::new(server_config));
+        let certified_key = Self::build_certified_key(svid)?;
+        self.identity.store(certified_key);
         self.spiffe_id = svid.spiffe_id().clone();
 
         Ok(())
     }
+
+    /// Update the configuration with a newly refreshed trust bundle
+    ///
+    /// Rebuilds the `RootCertStore` from `trust_bundle` and swaps it behind
+    /// [`SwappableRootStore`] in place, so handshakes already in flight keep
+    /// validating against the roots they started with while the very next
+    /// handshake picks up the refreshed trust anchors immediately.
+    pub fn update_bundle(&mut self, trust_bundle: &TrustBundle) -> Result<()> {
+        info!(
+            "Rotating mTLS trust bundle for trust domain {}",
+            trust_bundle.trust_domain()
+        );
+
+        let root_store = Self::build_root_store(trust_bundle)?;
+        self.roots.store(root_store);
+
+        Ok(())
+    }
 }
 
 /// mTLS connection validator
@@ -243,14 +808,13 @@ impl MtlsValidator {
     }
 
     /// Extract SPIFFE ID from X.509 certificate
-    fn extract_spiffe_id(&self, _cert_der: &[u8]) -> Result<crate::SpiffeId> {
-        // This is a placeholder - real implementation would:
-        // 1. Parse the X.509 certificate
-        // 2. Extract the SAN URI field
-        // 3. Validate it's a proper SPIFFE ID
-
-        // For now, return a dummy ID for testing
-        crate::SpiffeId::new("example.org", "/peer/service")
+    ///
+    /// Parses the leaf certificate's SAN extension and returns the single
+    /// `spiffe://` URI entry. Per the SPIFFE X.509-SVID spec, exactly one
+    /// URI SAN must be present; certificates with zero or multiple are
+    /// rejected.
+    fn extract_spiffe_id(&self, cert_der: &[u8]) -> Result<crate::SpiffeId> {
+        extract_spiffe_id_from_der(cert_der)
     }
 }
 
@@ -268,6 +832,7 @@ mod tests {
             spiffe_id,
             vec![vec![1, 2, 3]], // Mock cert chain
             vec![4, 5, 6],       // Mock private key
+            Utc::now(),
             Utc::now() + chrono::Duration::hours(1),
             "12345".to_string(),
         ).unwrap();
@@ -298,4 +863,28 @@ mod tests {
         let result = validator.validate_peer_cert(&[]);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_verify_chain_for_server_auth_accepts_uri_san_only_cert() {
+        // A real SVID carries only a URI SAN, never a DNS/IP SAN. The old
+        // implementation routed this through `WebPkiServerVerifier`, which
+        // checks the leaf against a `ServerName` and can never match a
+        // SPIFFE ID — this regression test drives `verify_chain_for_server_auth`
+        // with a real generated cert to make sure that can't happen again.
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let mut params = rcgen::CertificateParams::default();
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        params.is_ca = rcgen::IsCa::NoCa;
+        params.subject_alt_names = vec![rcgen::SanType::URI(
+            rcgen::Ia5String::try_from("spiffe://example.org/service/web").unwrap(),
+        )];
+        let cert = params.self_signed(&key_pair).unwrap();
+        let cert_der = CertificateDer::from(cert.der().to_vec());
+
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(cert_der.clone()).unwrap();
+
+        let result = verify_chain_for_server_auth(&cert_der, &[], &roots, UnixTime::now());
+        assert!(result.is_ok());
+    }
 }
\ No newline at end of file
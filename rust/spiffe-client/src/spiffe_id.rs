@@ -132,6 +132,52 @@ impl SpiffeId {
         self.trust_domain == trust_domain
     }
 
+    /// Build a SPIFFE ID for the conventional Kubernetes workload path
+    /// `/ns/<namespace>/sa/<service-account>`
+    ///
+    /// # Examples
+    /// ```
+    /// use spiffe_client::SpiffeId;
+    ///
+    /// let id = SpiffeId::from_k8s("example.org", "payments", "web").unwrap();
+    /// assert_eq!(id.to_string(), "spiffe://example.org/ns/payments/sa/web");
+    /// assert_eq!(id.namespace(), Some("payments"));
+    /// assert_eq!(id.service_account(), Some("web"));
+    /// ```
+    pub fn from_k8s(
+        trust_domain: impl AsRef<str>,
+        namespace: impl AsRef<str>,
+        service_account: impl AsRef<str>,
+    ) -> Result<Self> {
+        Self::new(
+            trust_domain,
+            format!("/ns/{}/sa/{}", namespace.as_ref(), service_account.as_ref()),
+        )
+    }
+
+    /// The `ns` value of a path following the `/ns/<namespace>/sa/<service-account>`
+    /// convention, or `None` if the path doesn't follow it
+    pub fn namespace(&self) -> Option<&str> {
+        self.k8s_segment("ns")
+    }
+
+    /// The `sa` value of a path following the `/ns/<namespace>/sa/<service-account>`
+    /// convention, or `None` if the path doesn't follow it
+    pub fn service_account(&self) -> Option<&str> {
+        self.k8s_segment("sa")
+    }
+
+    /// Walk the path segments pairwise looking for `key`, returning the
+    /// value that follows it
+    fn k8s_segment(&self, key: &str) -> Option<&str> {
+        let segments: Vec<&str> = self.path.split('/').filter(|s| !s.is_empty()).collect();
+        segments
+            .chunks(2)
+            .find(|pair| pair.first() == Some(&key))
+            .and_then(|pair| pair.get(1))
+            .copied()
+    }
+
     /// Validate that this ID matches expected patterns
     pub fn validate(&self) -> Result<()> {
         // Trust domain validation
@@ -169,6 +215,130 @@ impl FromStr for SpiffeId {
     }
 }
 
+/// A single segment of a [`SpiffeIdMatcher`] path pattern: either a concrete
+/// string that must match exactly, or a wildcard matching any one segment
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum PathSegment {
+    Exact(String),
+    Wildcard,
+}
+
+/// A pattern over SPIFFE IDs, matching a concrete trust domain and a path
+/// whose segments may be concrete strings or a `*` wildcard
+///
+/// # Examples
+/// ```
+/// use spiffe_client::{SpiffeId, SpiffeIdMatcher};
+///
+/// let matcher: SpiffeIdMatcher = "spiffe://example.org/ns/*/sa/web".parse().unwrap();
+/// let id = SpiffeId::parse("spiffe://example.org/ns/payments/sa/web").unwrap();
+/// assert!(matcher.matches(&id));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SpiffeIdMatcher {
+    trust_domain: String,
+    segments: Vec<PathSegment>,
+    raw: String,
+}
+
+impl SpiffeIdMatcher {
+    /// Parse a `spiffe://` pattern where any path segment equal to `*` is
+    /// treated as a wildcard
+    pub fn parse(s: impl AsRef<str>) -> Result<Self> {
+        let raw = s.as_ref();
+        let rest = raw
+            .strip_prefix("spiffe://")
+            .ok_or_else(|| Error::invalid_spiffe_id(format!("Invalid scheme in pattern '{}'", raw)))?;
+
+        let mut parts = rest.splitn(2, '/');
+        let trust_domain = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| Error::invalid_spiffe_id(format!("Missing trust domain in pattern '{}'", raw)))?;
+        let path = parts.next().unwrap_or("");
+
+        if path.is_empty() {
+            return Err(Error::invalid_spiffe_id(format!(
+                "Pattern '{}' must have a non-empty path",
+                raw
+            )));
+        }
+
+        let segments = path
+            .split('/')
+            .filter(|seg| !seg.is_empty())
+            .map(|seg| {
+                if seg == "*" {
+                    PathSegment::Wildcard
+                } else {
+                    PathSegment::Exact(seg.to_string())
+                }
+            })
+            .collect();
+
+        Ok(SpiffeIdMatcher {
+            trust_domain: trust_domain.to_string(),
+            segments,
+            raw: raw.to_string(),
+        })
+    }
+
+    /// Returns `true` if `id` matches this pattern: equal trust domain, equal
+    /// segment count, and every concrete pattern segment equal to the
+    /// corresponding path segment (wildcards match any single segment)
+    pub fn matches(&self, id: &SpiffeId) -> bool {
+        if id.trust_domain() != self.trust_domain {
+            return false;
+        }
+
+        let id_segments: Vec<&str> = id.path().split('/').filter(|s| !s.is_empty()).collect();
+        if id_segments.len() != self.segments.len() {
+            return false;
+        }
+
+        id_segments
+            .iter()
+            .zip(self.segments.iter())
+            .all(|(seg, pattern)| match pattern {
+                PathSegment::Wildcard => true,
+                PathSegment::Exact(expected) => seg == expected,
+            })
+    }
+}
+
+impl fmt::Display for SpiffeIdMatcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+impl FromStr for SpiffeIdMatcher {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl Serialize for SpiffeIdMatcher {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for SpiffeIdMatcher {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -218,4 +388,55 @@ mod tests {
         assert!(id.is_member_of("example.org"));
         assert!(!id.is_member_of("other.org"));
     }
+
+    #[test]
+    fn test_spiffe_id_from_k8s() {
+        let id = SpiffeId::from_k8s("example.org", "payments", "web").unwrap();
+        assert_eq!(id.to_string(), "spiffe://example.org/ns/payments/sa/web");
+        assert_eq!(id.namespace(), Some("payments"));
+        assert_eq!(id.service_account(), Some("web"));
+    }
+
+    #[test]
+    fn test_spiffe_id_k8s_helpers_on_non_k8s_path() {
+        let id = SpiffeId::new("example.org", "/service/web").unwrap();
+        assert_eq!(id.namespace(), None);
+        assert_eq!(id.service_account(), None);
+    }
+
+    #[test]
+    fn test_spiffe_id_matcher_wildcard() {
+        let matcher: SpiffeIdMatcher = "spiffe://example.org/ns/*/sa/web".parse().unwrap();
+        let id = SpiffeId::parse("spiffe://example.org/ns/payments/sa/web").unwrap();
+        assert!(matcher.matches(&id));
+
+        let wrong_domain = SpiffeId::parse("spiffe://other.org/ns/payments/sa/web").unwrap();
+        assert!(!matcher.matches(&wrong_domain));
+
+        let wrong_segment = SpiffeId::parse("spiffe://example.org/ns/payments/sa/db").unwrap();
+        assert!(!matcher.matches(&wrong_segment));
+
+        let wrong_length = SpiffeId::parse("spiffe://example.org/ns/payments/sa/web/extra").unwrap();
+        assert!(!matcher.matches(&wrong_length));
+    }
+
+    #[test]
+    fn test_spiffe_id_matcher_exact() {
+        let matcher: SpiffeIdMatcher = "spiffe://example.org/service/web".parse().unwrap();
+        let id = SpiffeId::parse("spiffe://example.org/service/web").unwrap();
+        assert!(matcher.matches(&id));
+
+        let other = SpiffeId::parse("spiffe://example.org/service/db").unwrap();
+        assert!(!matcher.matches(&other));
+    }
+
+    #[test]
+    fn test_spiffe_id_matcher_display_roundtrip() {
+        let matcher: SpiffeIdMatcher = "spiffe://example.org/ns/*/sa/web".parse().unwrap();
+        assert_eq!(matcher.to_string(), "spiffe://example.org/ns/*/sa/web");
+
+        let json = serde_json::to_string(&matcher).unwrap();
+        let deserialized: SpiffeIdMatcher = serde_json::from_str(&json).unwrap();
+        assert_eq!(matcher, deserialized);
+    }
 }
\ No newline at end of file
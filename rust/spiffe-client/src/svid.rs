@@ -5,6 +5,11 @@ use crate::spiffe_id::SpiffeId;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use p256::pkcs8::DecodePrivateKey;
+use p256::SecretKey;
+use x509_parser::extensions::GeneralName;
+use x509_parser::prelude::{FromDer, ParsedExtension, X509Certificate};
 
 /// X.509 SVID for mTLS authentication
 #[derive(Clone, Debug)]
@@ -15,6 +20,8 @@ pub struct X509Svid {
     cert_chain: Vec<Vec<u8>>,
     /// Private key (DER encoded)
     private_key: Vec<u8>,
+    /// Certificate validity start time
+    not_before: DateTime<Utc>,
     /// Certificate expiration time
     expiry: DateTime<Utc>,
     /// Certificate serial number
@@ -27,6 +34,7 @@ impl X509Svid {
         spiffe_id: SpiffeId,
         cert_chain: Vec<Vec<u8>>,
         private_key: Vec<u8>,
+        not_before: DateTime<Utc>,
         expiry: DateTime<Utc>,
         serial_number: String,
     ) -> Result<Self> {
@@ -38,6 +46,10 @@ impl X509Svid {
             return Err(Error::X509Error("Private key cannot be empty".into()));
         }
 
+        if not_before >= expiry {
+            return Err(Error::X509Error("Certificate not_before must precede expiry".into()));
+        }
+
         // Validate SPIFFE ID
         spiffe_id.validate()?;
 
@@ -45,11 +57,68 @@ impl X509Svid {
             spiffe_id,
             cert_chain,
             private_key,
+            not_before,
             expiry,
             serial_number,
         })
     }
 
+    /// Parse an X.509-SVID straight from its DER certificate chain (leaf
+    /// first) and private key, instead of trusting separately-supplied
+    /// `expiry`/`serial_number`/`SpiffeId` that could silently disagree with
+    /// the certificate itself
+    ///
+    /// Per the SPIFFE X.509 profile, the leaf must carry exactly one
+    /// `spiffe://` URI SAN; any other count is rejected as malformed.
+    pub fn from_der(cert_chain: Vec<Vec<u8>>, private_key: Vec<u8>) -> Result<Self> {
+        if cert_chain.is_empty() {
+            return Err(Error::X509Error("Certificate chain cannot be empty".into()));
+        }
+
+        let (_, leaf) = X509Certificate::from_der(&cert_chain[0])
+            .map_err(|e| Error::X509Error(format!("Failed to parse leaf certificate: {}", e)))?;
+
+        let not_before = DateTime::<Utc>::from_timestamp(leaf.validity().not_before.timestamp(), 0)
+            .ok_or_else(|| Error::X509Error("Invalid notBefore timestamp".into()))?;
+        let expiry = DateTime::<Utc>::from_timestamp(leaf.validity().not_after.timestamp(), 0)
+            .ok_or_else(|| Error::X509Error("Invalid notAfter timestamp".into()))?;
+        let serial_number = leaf.raw_serial_as_string();
+
+        let mut spiffe_uris = Vec::new();
+        for ext in leaf.extensions() {
+            if let ParsedExtension::SubjectAlternativeName(san) = ext.parsed_extension() {
+                for name in &san.general_names {
+                    if let GeneralName::URI(uri) = name {
+                        if uri.starts_with("spiffe://") {
+                            spiffe_uris.push(*uri);
+                        }
+                    }
+                }
+            }
+        }
+
+        let spiffe_id = match spiffe_uris.as_slice() {
+            [uri] => SpiffeId::parse(*uri)?,
+            [] => return Err(Error::X509Error("Leaf certificate has no SPIFFE URI SAN".into())),
+            _ => return Err(Error::X509Error(format!(
+                "Leaf certificate must carry exactly one SPIFFE URI SAN, found {}",
+                spiffe_uris.len()
+            ))),
+        };
+
+        let secret_key = SecretKey::from_pkcs8_der(&private_key)
+            .map_err(|e| Error::X509Error(format!("Failed to parse private key: {}", e)))?;
+        let derived_public_key = secret_key.public_key().to_encoded_point(false);
+        let leaf_public_key = leaf.public_key().subject_public_key.as_ref();
+        if derived_public_key.as_bytes() != leaf_public_key {
+            return Err(Error::X509Error(
+                "Private key does not match the leaf certificate's public key".into(),
+            ));
+        }
+
+        Self::new(spiffe_id, cert_chain, private_key, not_before, expiry, serial_number)
+    }
+
     /// Get the SPIFFE ID
     pub fn spiffe_id(&self) -> &SpiffeId {
         &self.spiffe_id
@@ -80,6 +149,11 @@ impl X509Svid {
         &self.expiry
     }
 
+    /// Get the validity start time
+    pub fn not_before(&self) -> &DateTime<Utc> {
+        &self.not_before
+    }
+
     /// Get time until expiration
     pub fn time_until_expiry(&self) -> chrono::Duration {
         self.expiry - Utc::now()
@@ -101,12 +175,31 @@ impl X509Svid {
         Ok(())
     }
 
-    /// Check if rotation is needed (within 30% of lifetime)
-    pub fn needs_rotation(&self) -> bool {
-        let time_until = self.time_until_expiry();
-        let total_lifetime = self.expiry - Utc::now() + chrono::Duration::hours(24); // Approximate
+    /// The point in the SVID's actual validity window at which rotation
+    /// should be triggered
+    ///
+    /// `rotation_threshold` is the fraction of `not_before..expiry` that may
+    /// elapse before renewal; e.g. `0.5` renews halfway through the SVID's
+    /// lifetime. The deadline is always clamped to at least
+    /// `pre_expiry_margin` before `expiry`, so a short-lived SVID with a
+    /// generous threshold still gets a final chance to rotate before it
+    /// expires outright.
+    pub fn rotation_deadline(&self, rotation_threshold: f64, pre_expiry_margin: chrono::Duration) -> DateTime<Utc> {
+        let lifetime_ms = (self.expiry - self.not_before).num_milliseconds() as f64;
+        let elapsed_ms = lifetime_ms * rotation_threshold.clamp(0.0, 1.0);
+        let by_threshold = self.not_before + chrono::Duration::milliseconds(elapsed_ms as i64);
+        let by_margin = self.expiry - pre_expiry_margin;
+
+        by_threshold.min(by_margin)
+    }
 
-        time_until < total_lifetime / 3
+    /// Check if rotation is needed, based on the SVID's actual validity
+    /// window rather than a flat interval
+    ///
+    /// See [`Self::rotation_deadline`] for how `rotation_threshold` and
+    /// `pre_expiry_margin` are applied.
+    pub fn needs_rotation(&self, rotation_threshold: f64, pre_expiry_margin: chrono::Duration) -> bool {
+        Utc::now() >= self.rotation_deadline(rotation_threshold, pre_expiry_margin)
     }
 }
 
@@ -222,9 +315,13 @@ impl SvidBundle {
     }
 
     /// Check if any SVIDs need rotation
-    pub fn needs_rotation(&self) -> bool {
+    ///
+    /// See [`X509Svid::needs_rotation`] for how `rotation_threshold` and
+    /// `pre_expiry_margin` are applied to the X.509 SVID; JWT-SVIDs have no
+    /// proactive renewal path, so they're simply checked for expiry.
+    pub fn needs_rotation(&self, rotation_threshold: f64, pre_expiry_margin: chrono::Duration) -> bool {
         if let Some(x509) = &self.x509_svid {
-            if x509.needs_rotation() {
+            if x509.needs_rotation(rotation_threshold, pre_expiry_margin) {
                 return true;
             }
         }
@@ -242,6 +339,7 @@ mod tests {
         let spiffe_id = SpiffeId::new("example.org", "/service/web").unwrap();
         let cert_chain = vec![vec![1, 2, 3]];
         let private_key = vec![4, 5, 6];
+        let not_before = Utc::now();
         let expiry = Utc::now() + chrono::Duration::hours(1);
         let serial = "12345".to_string();
 
@@ -249,6 +347,7 @@ mod tests {
             spiffe_id.clone(),
             cert_chain,
             private_key,
+            not_before,
             expiry,
             serial,
         ).unwrap();
@@ -258,6 +357,31 @@ mod tests {
         assert_eq!(svid.serial_number(), "12345");
     }
 
+    #[test]
+    fn test_x509_svid_from_der_parses_leaf() {
+        let id = SpiffeId::new("example.org", "/service/web").unwrap();
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let mut params = rcgen::CertificateParams::default();
+        params.distinguished_name = rcgen::DistinguishedName::new();
+        params.subject_alt_names = vec![rcgen::SanType::URI(
+            rcgen::Ia5String::try_from(id.to_string()).unwrap(),
+        )];
+        let cert = params.self_signed(&key_pair).unwrap();
+
+        let svid = X509Svid::from_der(vec![cert.der().to_vec()], key_pair.serialize_der()).unwrap();
+        assert_eq!(svid.spiffe_id(), &id);
+    }
+
+    #[test]
+    fn test_x509_svid_from_der_rejects_missing_spiffe_san() {
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let params = rcgen::CertificateParams::default();
+        let cert = params.self_signed(&key_pair).unwrap();
+
+        let result = X509Svid::from_der(vec![cert.der().to_vec()], key_pair.serialize_der());
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_jwt_svid_creation() {
         let spiffe_id = SpiffeId::new("example.org", "/service/web").unwrap();
@@ -294,6 +418,7 @@ mod tests {
         let spiffe_id = SpiffeId::new("example.org", "/service/web").unwrap();
         let cert_chain = vec![vec![1, 2, 3]];
         let private_key = vec![4, 5, 6];
+        let not_before = Utc::now() - chrono::Duration::hours(2);
         let expiry = Utc::now() - chrono::Duration::hours(1); // Already expired
         let serial = "12345".to_string();
 
@@ -301,6 +426,7 @@ mod tests {
             spiffe_id,
             cert_chain,
             private_key,
+            not_before,
             expiry,
             serial,
         ).unwrap();
@@ -308,4 +434,29 @@ mod tests {
         assert!(svid.is_expired());
         assert!(svid.validate().is_err());
     }
+
+    #[test]
+    fn test_x509_svid_rotation_deadline() {
+        let spiffe_id = SpiffeId::new("example.org", "/service/web").unwrap();
+        let not_before = Utc::now() - chrono::Duration::minutes(30);
+        let expiry = Utc::now() + chrono::Duration::minutes(30);
+
+        let svid = X509Svid::new(
+            spiffe_id,
+            vec![vec![1, 2, 3]],
+            vec![4, 5, 6],
+            not_before,
+            expiry,
+            "12345".to_string(),
+        ).unwrap();
+
+        // Halfway through a 1h lifetime that started 30m ago: the 50%
+        // threshold deadline is already in the past, so rotation is due.
+        assert!(svid.needs_rotation(0.5, chrono::Duration::seconds(30)));
+
+        // A threshold near the very end of the window isn't due yet, but a
+        // pre-expiry margin longer than the 30m left forces rotation now.
+        assert!(!svid.needs_rotation(0.99, chrono::Duration::seconds(0)));
+        assert!(svid.needs_rotation(0.99, chrono::Duration::minutes(31)));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,121 @@
+//! A [`SvidBundle`]-shaped facade over [`ManagedWorkloadClient`]'s background rotation
+
+use crate::error::Result;
+use crate::svid::SvidBundle;
+use crate::workload::{BackoffConfig, ManagedWorkloadClient, WorkloadApiConfig};
+use arc_swap::ArcSwap;
+use std::sync::Arc;
+use tokio::sync::watch;
+
+/// How aggressively [`SvidManager`] proactively renews its SVID ahead of
+/// expiry; passed straight through to the underlying
+/// [`WorkloadApiConfig`](crate::workload::WorkloadApiConfig)
+#[derive(Clone, Debug)]
+pub struct RotationPolicy {
+    /// See [`crate::svid::X509Svid::rotation_deadline`]
+    pub rotation_threshold: f64,
+    /// See [`crate::svid::X509Svid::rotation_deadline`]
+    pub pre_expiry_margin: chrono::Duration,
+    /// Backoff applied to failed fetches, on both the push and
+    /// proactive-refresh sides
+    pub backoff: BackoffConfig,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        RotationPolicy {
+            rotation_threshold: 0.5,
+            pre_expiry_margin: chrono::Duration::seconds(60),
+            backoff: BackoffConfig::default(),
+        }
+    }
+}
+
+/// Holds a workload's current [`SvidBundle`] behind an `ArcSwap`, kept
+/// rotated by a [`ManagedWorkloadClient`] underneath
+///
+/// [`ManagedWorkloadClient`] already owns the one rotation subsystem in this
+/// crate: a push-stream watch plus a deadline-scheduled proactive refresh,
+/// both with reconnect-with-backoff. `SvidManager` doesn't run any rotation
+/// of its own — it only adapts that client's `Option<X509Svid>` updates into
+/// the `SvidBundle` shape, behind a lock-free [`Self::current`] for any
+/// number of concurrent readers and an [`Self::subscribe`] for dependents
+/// that want to react to a rotation in place.
+pub struct SvidManager {
+    current: ArcSwap<SvidBundle>,
+    updates: watch::Sender<Arc<SvidBundle>>,
+    // Kept alive so its background rotation tasks keep running for as long
+    // as the manager itself is alive.
+    _managed: ManagedWorkloadClient,
+}
+
+impl SvidManager {
+    /// Start a [`ManagedWorkloadClient`] against `socket_path` and adapt its
+    /// rotations into an `SvidBundle`
+    pub async fn new(socket_path: String, policy: RotationPolicy) -> Result<Arc<Self>> {
+        let config = WorkloadApiConfig {
+            socket_path,
+            auto_rotate: true,
+            rotation_threshold: policy.rotation_threshold,
+            pre_expiry_margin: policy
+                .pre_expiry_margin
+                .to_std()
+                .unwrap_or(std::time::Duration::from_secs(60)),
+            cache_bundles: true,
+            backoff: policy.backoff,
+        };
+
+        let managed = ManagedWorkloadClient::new(config).await?;
+        let rotations = managed.subscribe();
+
+        let initial = rotations.borrow().clone();
+        let bundle = Arc::new(SvidBundle::new(initial));
+        let (updates, _) = watch::channel(bundle.clone());
+
+        let manager = Arc::new(SvidManager {
+            current: ArcSwap::new(bundle),
+            updates,
+            _managed: managed,
+        });
+
+        manager.clone().spawn_bundle_adapter(rotations);
+
+        Ok(manager)
+    }
+
+    /// The most recently rotated SVID bundle
+    pub fn current(&self) -> Arc<SvidBundle> {
+        self.current.load_full()
+    }
+
+    /// Subscribe to every rotation; the receiver's initial value is the
+    /// bundle active at subscription time
+    pub fn subscribe(&self) -> watch::Receiver<Arc<SvidBundle>> {
+        self.updates.subscribe()
+    }
+
+    /// Mirror every `ManagedWorkloadClient` rotation into this manager's own
+    /// `ArcSwap`/`watch` pair
+    fn spawn_bundle_adapter(self: Arc<Self>, mut rotations: watch::Receiver<Option<crate::svid::X509Svid>>) {
+        tokio::spawn(async move {
+            while rotations.changed().await.is_ok() {
+                let svid = rotations.borrow().clone();
+                let bundle = Arc::new(SvidBundle::new(svid));
+                self.current.store(bundle.clone());
+                let _ = self.updates.send(bundle);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotation_policy_default() {
+        let policy = RotationPolicy::default();
+        assert_eq!(policy.rotation_threshold, 0.5);
+        assert_eq!(policy.pre_expiry_margin, chrono::Duration::seconds(60));
+    }
+}
@@ -1,11 +1,37 @@
 //! Trust bundle management for SPIFFE
 
 use crate::error::{Error, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64_STANDARD, Engine};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::io::Cursor;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// The JWK `use` value the SPIFFE trust bundle format uses for X.509 roots
+const SPIFFE_BUNDLE_X509_USE: &str = "x509-svid";
+
+/// The JWK `use` value the SPIFFE trust bundle format uses for JWT-SVID
+/// signing keys
+const SPIFFE_BUNDLE_JWT_USE: &str = "jwt-svid";
+
+/// A JWT-SVID signing key published in a trust bundle, keyed by its `kid`
+///
+/// Only EC keys are modeled here since that's what SPIRE issues today;
+/// [`crate::jwt::SpiffeJwtValidator`] rejects any other `kty`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JwtAuthority {
+    /// JOSE key type, e.g. `"EC"`
+    pub kty: String,
+    /// Curve, for EC keys, e.g. `"P-256"`
+    pub crv: Option<String>,
+    /// Base64url-encoded x coordinate, for EC keys
+    pub x: Option<String>,
+    /// Base64url-encoded y coordinate, for EC keys
+    pub y: Option<String>,
+}
 
 /// Trust bundle containing root certificates for a trust domain
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,8 +40,15 @@ pub struct TrustBundle {
     trust_domain: String,
     /// Root CA certificates (DER encoded)
     certificates: Vec<Vec<u8>>,
+    /// JWT-SVID signing keys, by `kid`
+    #[serde(default)]
+    jwt_authorities: HashMap<String, JwtAuthority>,
     /// Bundle sequence number for versioning
     sequence_number: u64,
+    /// How long a caller should wait before refetching this bundle, per the
+    /// document's `spiffe_refresh_hint`
+    #[serde(default)]
+    refresh_hint: Option<std::time::Duration>,
     /// Last update time
     updated_at: DateTime<Utc>,
 }
@@ -26,7 +59,9 @@ impl TrustBundle {
         TrustBundle {
             trust_domain,
             certificates,
+            jwt_authorities: HashMap::new(),
             sequence_number: 0,
+            refresh_hint: None,
             updated_at: Utc::now(),
         }
     }
@@ -40,11 +75,146 @@ impl TrustBundle {
         TrustBundle {
             trust_domain,
             certificates,
+            jwt_authorities: HashMap::new(),
             sequence_number,
+            refresh_hint: None,
             updated_at: Utc::now(),
         }
     }
 
+    /// Build a trust bundle from a PEM document containing one or more
+    /// `CERTIFICATE` blocks
+    pub fn from_pem(trust_domain: String, pem: &str) -> Result<Self> {
+        let mut reader = Cursor::new(pem.as_bytes());
+        let certificates: Vec<Vec<u8>> = rustls_pemfile::certs(&mut reader)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::TrustBundleError(format!("Failed to parse PEM: {}", e)))?
+            .into_iter()
+            .map(|cert| cert.to_vec())
+            .collect();
+
+        if certificates.is_empty() {
+            return Err(Error::TrustBundleError(
+                "PEM document contains no CERTIFICATE blocks".into(),
+            ));
+        }
+
+        Ok(Self::new(trust_domain, certificates))
+    }
+
+    /// Parse a standard SPIFFE trust bundle document (a JWKS-style JSON
+    /// object with a `spiffe_sequence` and a `keys` array)
+    ///
+    /// `keys` entries with `"use": "x509-svid"` carry their certificate
+    /// chain as base64-encoded DER in `x5c`; entries with
+    /// `"use": "jwt-svid"` are JWT-SVID signing keys, kept by `kid` for
+    /// [`crate::jwt::SpiffeJwtValidator`].
+    pub fn from_spiffe_bundle_json(trust_domain: String, json: &str) -> Result<Self> {
+        let doc: serde_json::Value = serde_json::from_str(json)
+            .map_err(|e| Error::TrustBundleError(format!("Failed to parse SPIFFE bundle JSON: {}", e)))?;
+
+        let sequence_number = doc.get("spiffe_sequence").and_then(|v| v.as_u64()).unwrap_or(0);
+        let refresh_hint = doc
+            .get("spiffe_refresh_hint")
+            .and_then(|v| v.as_u64())
+            .map(std::time::Duration::from_secs);
+
+        let keys = doc
+            .get("keys")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| Error::TrustBundleError("SPIFFE bundle JSON is missing a 'keys' array".into()))?;
+
+        let mut certificates = Vec::new();
+        let mut jwt_authorities = HashMap::new();
+        for key in keys {
+            match key.get("use").and_then(|v| v.as_str()) {
+                Some(SPIFFE_BUNDLE_X509_USE) => {
+                    let x5c = key
+                        .get("x5c")
+                        .and_then(|v| v.as_array())
+                        .ok_or_else(|| Error::TrustBundleError("x509-svid key is missing 'x5c'".into()))?;
+
+                    for cert_b64 in x5c {
+                        let cert_b64 = cert_b64
+                            .as_str()
+                            .ok_or_else(|| Error::TrustBundleError("x5c entry is not a string".into()))?;
+
+                        let der = BASE64_STANDARD
+                            .decode(cert_b64)
+                            .map_err(|e| Error::TrustBundleError(format!("Failed to decode x5c certificate: {}", e)))?;
+                        certificates.push(der);
+                    }
+                }
+                Some(SPIFFE_BUNDLE_JWT_USE) => {
+                    let kid = key
+                        .get("kid")
+                        .and_then(|v| v.as_str())
+                        .ok_or_else(|| Error::TrustBundleError("jwt-svid key is missing 'kid'".into()))?
+                        .to_string();
+
+                    let authority: JwtAuthority = serde_json::from_value(key.clone())
+                        .map_err(|e| Error::TrustBundleError(format!("Invalid jwt-svid key: {}", e)))?;
+
+                    jwt_authorities.insert(kid, authority);
+                }
+                _ => continue,
+            }
+        }
+
+        if certificates.is_empty() {
+            return Err(Error::TrustBundleError(
+                "SPIFFE bundle JSON contains no x509-svid roots".into(),
+            ));
+        }
+
+        let mut bundle = Self::with_sequence(trust_domain, certificates, sequence_number);
+        bundle.jwt_authorities = jwt_authorities;
+        bundle.refresh_hint = refresh_hint;
+        Ok(bundle)
+    }
+
+    /// Parse a standard SPIFFE trust bundle document for `trust_domain`
+    ///
+    /// Equivalent to [`Self::from_spiffe_bundle_json`] with the arguments in
+    /// `(document, trust_domain)` order, matching how callers typically have
+    /// the fetched JSON in hand before they know which domain it's for.
+    pub fn from_spiffe_json(json: &str, trust_domain: String) -> Result<Self> {
+        Self::from_spiffe_bundle_json(trust_domain, json)
+    }
+
+    /// Serialize this bundle as a standard SPIFFE trust bundle document
+    pub fn to_spiffe_bundle_json(&self) -> Result<String> {
+        let mut keys: Vec<serde_json::Value> = self
+            .certificates
+            .iter()
+            .map(|cert| {
+                serde_json::json!({
+                    "use": SPIFFE_BUNDLE_X509_USE,
+                    "x5c": [BASE64_STANDARD.encode(cert)],
+                })
+            })
+            .collect();
+
+        for (kid, authority) in &self.jwt_authorities {
+            let mut key = serde_json::to_value(authority)
+                .map_err(|e| Error::TrustBundleError(format!("Failed to serialize jwt-svid key: {}", e)))?;
+            key["use"] = serde_json::Value::String(SPIFFE_BUNDLE_JWT_USE.to_string());
+            key["kid"] = serde_json::Value::String(kid.clone());
+            keys.push(key);
+        }
+
+        let mut doc = serde_json::json!({
+            "spiffe_sequence": self.sequence_number,
+            "keys": keys,
+        });
+        if let Some(refresh_hint) = self.refresh_hint {
+            doc["spiffe_refresh_hint"] = serde_json::Value::Number(refresh_hint.as_secs().into());
+        }
+
+        serde_json::to_string(&doc)
+            .map_err(|e| Error::TrustBundleError(format!("Failed to serialize SPIFFE bundle JSON: {}", e)))
+    }
+
     /// Get the trust domain
     pub fn trust_domain(&self) -> &str {
         &self.trust_domain
@@ -60,6 +230,17 @@ impl TrustBundle {
         self.sequence_number
     }
 
+    /// Get the JWT-SVID signing keys published in this bundle, by `kid`
+    pub fn jwt_authorities(&self) -> &HashMap<String, JwtAuthority> {
+        &self.jwt_authorities
+    }
+
+    /// How long a caller should wait before refetching this bundle, if the
+    /// document carried a `spiffe_refresh_hint`
+    pub fn refresh_hint(&self) -> Option<std::time::Duration> {
+        self.refresh_hint
+    }
+
     /// Get the last update time
     pub fn updated_at(&self) -> &DateTime<Utc> {
         &self.updated_at
@@ -105,7 +286,9 @@ impl TrustBundle {
 
         if other.is_newer_than(self) {
             self.certificates = other.certificates.clone();
+            self.jwt_authorities = other.jwt_authorities.clone();
             self.sequence_number = other.sequence_number;
+            self.refresh_hint = other.refresh_hint;
             self.updated_at = other.updated_at;
             info!(
                 "Updated trust bundle for {} to sequence {}",
@@ -137,14 +320,57 @@ impl TrustBundle {
         Ok(())
     }
 
-    /// Remove expired certificates (placeholder - needs X.509 parsing)
-    pub fn prune_expired(&mut self) -> usize {
-        // In a real implementation, this would:
-        // 1. Parse each certificate
-        // 2. Check expiration dates
-        // 3. Remove expired ones
-        // For now, just return 0
-        0
+    /// Remove certificates whose `notAfter` has already passed
+    ///
+    /// Returns the number of certificates removed, bumping
+    /// `sequence_number`/`updated_at` if anything changed. Fails with
+    /// [`Error::TrustBundleError`] rather than silently keeping a
+    /// certificate that cannot be parsed.
+    pub fn prune_expired(&mut self) -> Result<usize> {
+        let now = Utc::now();
+        let mut retained = Vec::with_capacity(self.certificates.len());
+
+        for cert in &self.certificates {
+            let not_after = Self::not_after(cert)?;
+            if not_after >= now {
+                retained.push(cert.clone());
+            }
+        }
+
+        let pruned = self.certificates.len() - retained.len();
+        self.certificates = retained;
+
+        if pruned > 0 {
+            self.sequence_number += 1;
+            self.updated_at = Utc::now();
+            debug!(
+                "Pruned {} expired certificate(s) from trust bundle for {}",
+                pruned, self.trust_domain
+            );
+        }
+
+        Ok(pruned)
+    }
+
+    /// The soonest `notAfter` across all root certificates in this bundle
+    ///
+    /// Lets callers schedule a proactive bundle refresh ahead of the first
+    /// root expiring, rather than discovering it's gone stale after the
+    /// fact. Certificates that fail to parse are skipped.
+    pub fn earliest_expiry(&self) -> Option<DateTime<Utc>> {
+        self.certificates
+            .iter()
+            .filter_map(|cert| Self::not_after(cert).ok())
+            .min()
+    }
+
+    /// Parse a DER certificate's `notAfter` validity field
+    fn not_after(cert_der: &[u8]) -> Result<DateTime<Utc>> {
+        let (_, cert) = X509Certificate::from_der(cert_der)
+            .map_err(|e| Error::TrustBundleError(format!("Failed to parse certificate: {}", e)))?;
+
+        DateTime::<Utc>::from_timestamp(cert.validity().not_after.timestamp(), 0)
+            .ok_or_else(|| Error::TrustBundleError("Certificate has an invalid notAfter timestamp".into()))
     }
 }
 
@@ -238,12 +464,18 @@ impl TrustBundleStore {
     }
 
     /// Prune expired certificates from all bundles
+    ///
+    /// A bundle whose certificates fail to parse is left untouched and
+    /// logged, rather than aborting the sweep for every other domain.
     pub fn prune_all_expired(&self) -> usize {
         let mut total_pruned = 0;
         let mut bundles = self.bundles.write();
 
-        for bundle in bundles.values_mut() {
-            total_pruned += bundle.prune_expired();
+        for (trust_domain, bundle) in bundles.iter_mut() {
+            match bundle.prune_expired() {
+                Ok(pruned) => total_pruned += pruned,
+                Err(e) => warn!("Failed to prune expired certificates for {}: {}", trust_domain, e),
+            }
         }
 
         if total_pruned > 0 {
@@ -336,6 +568,81 @@ impl FederatedBundle {
 mod tests {
     use super::*;
 
+    /// A self-signed DER certificate with a controllable validity window, for
+    /// exercising [`TrustBundle::prune_expired`]/[`TrustBundle::earliest_expiry`]
+    fn self_signed_der(not_before: time::OffsetDateTime, not_after: time::OffsetDateTime) -> Vec<u8> {
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let mut params = rcgen::CertificateParams::default();
+        params.not_before = not_before;
+        params.not_after = not_after;
+        params.self_signed(&key_pair).unwrap().der().to_vec()
+    }
+
+    #[test]
+    fn test_prune_expired_removes_only_expired_certificates() {
+        let now = time::OffsetDateTime::now_utc();
+        let expired = self_signed_der(now - time::Duration::hours(2), now - time::Duration::hours(1));
+        let valid = self_signed_der(now - time::Duration::hours(1), now + time::Duration::hours(1));
+
+        let mut bundle = TrustBundle::new("example.org".to_string(), vec![expired, valid.clone()]);
+        let seq_before = bundle.sequence_number();
+
+        let pruned = bundle.prune_expired().unwrap();
+
+        assert_eq!(pruned, 1);
+        assert_eq!(bundle.certificates(), &[valid]);
+        assert_eq!(bundle.sequence_number(), seq_before + 1);
+    }
+
+    #[test]
+    fn test_prune_expired_aborts_entirely_if_any_certificate_is_unparseable() {
+        let now = time::OffsetDateTime::now_utc();
+        let valid = self_signed_der(now - time::Duration::hours(1), now + time::Duration::hours(1));
+        let garbage = vec![0xde, 0xad, 0xbe, 0xef];
+
+        let mut bundle = TrustBundle::new("example.org".to_string(), vec![valid, garbage]);
+        let seq_before = bundle.sequence_number();
+
+        let err = bundle.prune_expired().unwrap_err();
+        assert!(matches!(err, Error::TrustBundleError(_)));
+
+        // All-or-nothing: the one valid certificate wasn't pruned on its own
+        // either, the whole bundle is left exactly as it was.
+        assert_eq!(bundle.certificates().len(), 2);
+        assert_eq!(bundle.sequence_number(), seq_before);
+    }
+
+    #[test]
+    fn test_earliest_expiry_is_none_for_an_empty_bundle() {
+        let bundle = TrustBundle::new("example.org".to_string(), vec![]);
+        assert_eq!(bundle.earliest_expiry(), None);
+    }
+
+    #[test]
+    fn test_earliest_expiry_returns_the_minimum_even_when_every_certificate_is_expired() {
+        let now = time::OffsetDateTime::now_utc();
+        let sooner = self_signed_der(now - time::Duration::hours(3), now - time::Duration::hours(2));
+        let later = self_signed_der(now - time::Duration::hours(5), now - time::Duration::hours(1));
+
+        // `earliest_expiry` doesn't filter by expiry status, so the minimum
+        // `notAfter` across two already-expired certs is still returned.
+        let bundle = TrustBundle::new("example.org".to_string(), vec![later, sooner]);
+
+        let earliest = bundle.earliest_expiry().unwrap();
+        assert!(earliest < Utc::now() - chrono::Duration::minutes(90));
+    }
+
+    #[test]
+    fn test_earliest_expiry_skips_unparseable_certificates() {
+        let now = time::OffsetDateTime::now_utc();
+        let valid = self_signed_der(now - time::Duration::hours(1), now + time::Duration::hours(2));
+        let garbage = vec![0x00, 0x01, 0x02];
+
+        let bundle = TrustBundle::new("example.org".to_string(), vec![garbage, valid]);
+
+        assert!(bundle.earliest_expiry().is_some());
+    }
+
     #[test]
     fn test_trust_bundle_creation() {
         let bundle = TrustBundle::new(
@@ -419,4 +726,21 @@ mod tests {
         let unknown_id = crate::SpiffeId::new("unknown.org", "/service").unwrap();
         assert!(federated.validate_spiffe_id(&unknown_id).is_err());
     }
+
+    #[test]
+    fn test_spiffe_json_sequence_and_refresh_hint_roundtrip() {
+        let mut bundle = TrustBundle::with_sequence(
+            "example.org".to_string(),
+            vec![vec![1, 2, 3]],
+            7,
+        );
+        bundle.refresh_hint = Some(std::time::Duration::from_secs(3600));
+
+        let json = bundle.to_spiffe_bundle_json().unwrap();
+        let parsed = TrustBundle::from_spiffe_json(&json, "example.org".to_string()).unwrap();
+
+        assert_eq!(parsed.sequence_number(), 7);
+        assert_eq!(parsed.refresh_hint(), Some(std::time::Duration::from_secs(3600)));
+        assert_eq!(parsed.certificates(), bundle.certificates());
+    }
 }
\ No newline at end of file
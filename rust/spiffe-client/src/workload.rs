@@ -1,18 +1,197 @@
 //! Workload API client for SPIRE
 
 use crate::error::{Error, Result};
+use crate::proto::spiffe_workload_api_client::SpiffeWorkloadApiClient;
+use crate::proto::{JwtsvidRequest, ValidateJwtsvidRequest, X509SvidRequest, X509SvidResponse};
 use crate::spiffe_id::SpiffeId;
 use crate::svid::{JwtSvid, SvidBundle, X509Svid};
 use crate::trust_bundle::TrustBundle;
-// use futures::stream::StreamExt; // For future streaming implementation
+use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
+use hyper_util::rt::TokioIo;
 use std::sync::Arc;
 use tokio::sync::RwLock;
-use tonic::transport::Channel;
-use tracing::{debug, error, info, warn};
+use tokio::sync::watch;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+use tracing::{debug, info, warn};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Split a chain of concatenated DER certificates (as delivered by the
+/// Workload API, leaf first) into its individual certificates
+fn split_der_chain(chain: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut certs = Vec::new();
+    let mut rest = chain;
+
+    while !rest.is_empty() {
+        let (remainder, cert) = X509Certificate::from_der(rest)
+            .map_err(|e| Error::X509Error(format!("Failed to parse certificate in chain: {}", e)))?;
+        let consumed = rest.len() - remainder.len();
+        certs.push(rest[..consumed].to_vec());
+        rest = remainder;
+    }
+
+    if certs.is_empty() {
+        return Err(Error::X509Error("Certificate chain is empty".into()));
+    }
+
+    Ok(certs)
+}
+
+/// Read the `notBefore`/`notAfter` and serial number off a leaf certificate
+fn leaf_metadata(leaf_der: &[u8]) -> Result<(DateTime<Utc>, DateTime<Utc>, String)> {
+    let (_, cert) = X509Certificate::from_der(leaf_der)
+        .map_err(|e| Error::X509Error(format!("Failed to parse leaf certificate: {}", e)))?;
+
+    let not_before = DateTime::<Utc>::from_timestamp(cert.validity().not_before.timestamp(), 0)
+        .ok_or_else(|| Error::X509Error("Invalid notBefore timestamp".into()))?;
+    let expiry = DateTime::<Utc>::from_timestamp(cert.validity().not_after.timestamp(), 0)
+        .ok_or_else(|| Error::X509Error("Invalid notAfter timestamp".into()))?;
+    let serial_number = cert.raw_serial_as_string();
+
+    Ok((not_before, expiry, serial_number))
+}
+
+/// Convert a single `X509SVID` protobuf message into our domain type, plus
+/// the trust bundles it carries (own bundle + any federated bundles)
+fn convert_x509_svid(svid: &crate::proto::X509Svid) -> Result<(X509Svid, Vec<TrustBundle>)> {
+    let spiffe_id = SpiffeId::parse(&svid.spiffe_id)?;
+    let cert_chain = split_der_chain(&svid.x509_svid)?;
+    let (not_before, expiry, serial_number) = leaf_metadata(&cert_chain[0])?;
+
+    let x509_svid = X509Svid::new(
+        spiffe_id.clone(),
+        cert_chain,
+        svid.x509_svid_key.clone(),
+        not_before,
+        expiry,
+        serial_number,
+    )?;
+
+    let mut bundles = Vec::new();
+    if !svid.bundle.is_empty() {
+        let roots = split_der_chain(&svid.bundle)?;
+        bundles.push(TrustBundle::new(spiffe_id.trust_domain().to_string(), roots));
+    }
+    for (trust_domain, der) in &svid.federated_bundles {
+        let roots = split_der_chain(der)?;
+        bundles.push(TrustBundle::new(trust_domain.clone(), roots));
+    }
+
+    Ok((x509_svid, bundles))
+}
+
+/// Read the `exp` claim out of a JWT-SVID's payload without verifying its
+/// signature; the token just came from the agent over a trusted local
+/// channel, so this only recovers the expiry for our own bookkeeping
+fn jsonwebtoken_claims(token: &str) -> Result<DateTime<Utc>> {
+    use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+    let payload = token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| Error::JwtError("Malformed JWT-SVID".into()))?;
+
+    let decoded = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|e| Error::JwtError(format!("Failed to decode JWT payload: {}", e)))?;
+
+    let claims: serde_json::Value = serde_json::from_slice(&decoded)
+        .map_err(|e| Error::JwtError(format!("Failed to parse JWT payload: {}", e)))?;
+
+    let exp = claims
+        .get("exp")
+        .and_then(|v| v.as_i64())
+        .ok_or_else(|| Error::JwtError("JWT-SVID is missing an 'exp' claim".into()))?;
+
+    DateTime::<Utc>::from_timestamp(exp, 0)
+        .ok_or_else(|| Error::JwtError("JWT-SVID has an invalid 'exp' claim".into()))
+}
+
+/// Name of the metadata header the Workload API requires on every call, per
+/// the SPIFFE Workload Endpoint spec
+const WORKLOAD_API_SECURITY_HEADER: &str = "workload.spiffe.io";
+
+/// Wrap `message` in a gRPC request carrying the mandatory
+/// `workload.spiffe.io: true` security header
+fn workload_request<T>(message: T) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request.metadata_mut().insert(
+        WORKLOAD_API_SECURITY_HEADER,
+        tonic::metadata::MetadataValue::from_static("true"),
+    );
+    request
+}
+
+/// Take the first streamed `X509SVIDResponse` and convert its primary SVID
+fn primary_svid_from_response(response: X509SvidResponse) -> Result<(X509Svid, Vec<TrustBundle>)> {
+    let svid = response
+        .svids
+        .first()
+        .ok_or_else(|| Error::agent_error("Agent returned an X509SVIDResponse with no SVIDs"))?;
+
+    convert_x509_svid(svid)
+}
 
 /// Default SPIRE agent socket path
 pub const DEFAULT_SOCKET_PATH: &str = "/tmp/spire-agent/public/api.sock";
 
+/// Name of the environment variable SPIRE agents use to advertise their
+/// Workload API socket, per the SPIFFE Workload Endpoint spec
+pub const SPIFFE_ENDPOINT_SOCKET_ENV: &str = "SPIFFE_ENDPOINT_SOCKET";
+
+/// Strip a `unix:` scheme prefix from a socket address, if present
+fn normalize_socket_path(socket_path: &str) -> &str {
+    socket_path
+        .strip_prefix("unix:")
+        .unwrap_or(socket_path)
+}
+
+/// Exponential backoff policy used when the agent connection or the SVID
+/// stream is interrupted
+///
+/// The delay for a given attempt is `min(max_delay, base_delay *
+/// multiplier^attempt)`, with up to 50% random jitter added on top so that
+/// many clients reconnecting at once don't all retry in lockstep.
+#[derive(Clone, Debug)]
+pub struct BackoffConfig {
+    /// Delay before the first retry
+    pub base_delay: std::time::Duration,
+    /// Upper bound on the computed delay, regardless of attempt count
+    pub max_delay: std::time::Duration,
+    /// Factor the delay is multiplied by after each failed attempt
+    pub multiplier: f64,
+    /// Add up to 50% random jitter on top of the computed delay
+    pub jitter: bool,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        BackoffConfig {
+            base_delay: std::time::Duration::from_millis(500),
+            max_delay: std::time::Duration::from_secs(60),
+            multiplier: 2.0,
+            jitter: true,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Compute the delay to wait before the given (0-indexed) retry attempt
+    pub(crate) fn delay_for(&self, attempt: u32) -> std::time::Duration {
+        let scaled = self.base_delay.as_secs_f64() * self.multiplier.powi(attempt as i32);
+        let capped = scaled.min(self.max_delay.as_secs_f64());
+
+        let jittered = if self.jitter {
+            capped * (1.0 + rand::random::<f64>() * 0.5)
+        } else {
+            capped
+        };
+
+        std::time::Duration::from_secs_f64(jittered)
+    }
+}
+
 /// Workload API client for fetching SVIDs and bundles
 pub struct WorkloadApiClient {
     /// gRPC channel to SPIRE agent
@@ -27,8 +206,13 @@ pub struct WorkloadApiClient {
 
 impl WorkloadApiClient {
     /// Create a new Workload API client
+    ///
+    /// If no socket path is supplied, falls back to the `SPIFFE_ENDPOINT_SOCKET`
+    /// environment variable and finally to [`DEFAULT_SOCKET_PATH`].
     pub async fn new(socket_path: Option<String>) -> Result<Self> {
-        let socket_path = socket_path.unwrap_or_else(|| DEFAULT_SOCKET_PATH.to_string());
+        let socket_path = socket_path
+            .or_else(|| std::env::var(SPIFFE_ENDPOINT_SOCKET_ENV).ok())
+            .unwrap_or_else(|| DEFAULT_SOCKET_PATH.to_string());
 
         info!("Connecting to SPIRE agent at: {}", socket_path);
 
@@ -45,30 +229,65 @@ impl WorkloadApiClient {
     }
 
     /// Connect to SPIRE agent via Unix socket
-    async fn connect_to_agent(_socket_path: &str) -> Result<Channel> {
-        // In a real implementation, this would use Unix socket transport
-        // For now, we'll create a placeholder channel
-        let endpoint = tonic::transport::Endpoint::from_static("http://[::1]:50051")
+    ///
+    /// Accepts either a `unix:`-prefixed address or a raw filesystem path, both
+    /// pointing at the agent's Workload API Unix domain socket.
+    async fn connect_to_agent(socket_path: &str) -> Result<Channel> {
+        let path = normalize_socket_path(socket_path).to_string();
+
+        // The URI is never actually dialed; the connector below always
+        // redirects to the Unix socket path captured in the closure.
+        let endpoint = Endpoint::try_from("http://[::]:0")
+            .map_err(|e| Error::agent_error(format!("Invalid agent endpoint: {}", e)))?
             .connect_timeout(std::time::Duration::from_secs(5))
             .timeout(std::time::Duration::from_secs(10));
 
         endpoint
-            .connect()
+            .connect_with_connector(service_fn(move |_: Uri| {
+                let path = path.clone();
+                async move {
+                    let stream = tokio::net::UnixStream::connect(path).await?;
+                    Ok::<_, std::io::Error>(TokioIo::new(stream))
+                }
+            }))
             .await
             .map_err(|e| Error::agent_error(format!("Failed to connect to agent: {}", e)))
     }
 
+    /// Build a Workload API gRPC client bound to this client's channel
+    fn grpc_client(&self) -> SpiffeWorkloadApiClient<Channel> {
+        SpiffeWorkloadApiClient::new(self.channel.clone())
+    }
+
+    /// The socket path this client was created with, e.g. to pass to
+    /// [`Self::watch_x509_svid`] for reconnection
+    pub fn socket_path(&self) -> &str {
+        &self.socket_path
+    }
+
     /// Fetch X.509 SVID from SPIRE agent
+    ///
+    /// Opens the server-streaming `FetchX509SVID` RPC and returns the first
+    /// message's primary SVID; callers that want every subsequent rotation
+    /// should use [`Self::watch_x509_svid`] instead.
     pub async fn fetch_x509_svid(&self) -> Result<X509Svid> {
         info!("Fetching X.509 SVID from SPIRE agent");
 
-        // In a real implementation, this would:
-        // 1. Call the Workload API FetchX509SVID RPC
-        // 2. Parse the response
-        // 3. Create X509Svid from the response
+        let mut stream = self
+            .grpc_client()
+            .fetch_x509_svid(workload_request(X509SvidRequest {}))
+            .await
+            .map_err(|e| Error::agent_error(format!("FetchX509SVID failed: {}", e)))?
+            .into_inner();
+
+        let response = stream
+            .message()
+            .await
+            .map_err(|e| Error::agent_error(format!("FetchX509SVID stream error: {}", e)))?
+            .ok_or_else(|| Error::agent_error("Agent closed the X509SVID stream with no response"))?;
 
-        // For now, return a placeholder
-        Err(Error::agent_error("Not implemented yet"))
+        let (svid, _bundles) = primary_svid_from_response(response)?;
+        Ok(svid)
     }
 
     /// Fetch JWT SVID for specific audience
@@ -79,44 +298,155 @@ impl WorkloadApiClient {
             return Err(Error::agent_error("Audience cannot be empty"));
         }
 
-        // In a real implementation, this would:
-        // 1. Call the Workload API FetchJWTSVID RPC
-        // 2. Parse the JWT response
-        // 3. Create JwtSvid from the response
+        let response = self
+            .grpc_client()
+            .fetch_jwtsvid(workload_request(JwtsvidRequest {
+                audience: audience.clone(),
+                spiffe_id: String::new(),
+            }))
+            .await
+            .map_err(|e| Error::agent_error(format!("FetchJWTSVID failed: {}", e)))?
+            .into_inner();
+
+        let svid = response
+            .svids
+            .first()
+            .ok_or_else(|| Error::agent_error("Agent returned a JWTSVIDResponse with no SVIDs"))?;
 
-        Err(Error::agent_error("Not implemented yet"))
+        let spiffe_id = SpiffeId::parse(&svid.spiffe_id)?;
+        let claims = jsonwebtoken_claims(&svid.svid)?;
+
+        JwtSvid::new(spiffe_id, svid.svid.clone(), claims, audience)
     }
 
     /// Fetch trust bundles from SPIRE agent
+    ///
+    /// Piggybacks on the X.509 SVID stream, which carries both the caller's
+    /// own trust bundle and any federated bundles the agent has cached.
     pub async fn fetch_bundles(&self) -> Result<Vec<TrustBundle>> {
         info!("Fetching trust bundles from SPIRE agent");
 
-        // In a real implementation, this would:
-        // 1. Call the Workload API FetchJWTBundles or FetchX509Bundles RPC
-        // 2. Parse the response
-        // 3. Create TrustBundle objects
+        let mut stream = self
+            .grpc_client()
+            .fetch_x509_svid(workload_request(X509SvidRequest {}))
+            .await
+            .map_err(|e| Error::agent_error(format!("FetchX509SVID (bundle fetch) failed: {}", e)))?
+            .into_inner();
 
-        Err(Error::agent_error("Not implemented yet"))
+        let response = stream
+            .message()
+            .await
+            .map_err(|e| Error::agent_error(format!("FetchX509SVID (bundle fetch) stream error: {}", e)))?
+            .ok_or_else(|| Error::agent_error("Agent closed the bundle stream with no response"))?;
+
+        let (_svid, bundles) = primary_svid_from_response(response)?;
+        Ok(bundles)
     }
 
     /// Watch for SVID updates (streaming)
-    pub async fn watch_x509_svid<F>(&self, _callback: F) -> Result<()>
+    ///
+    /// Opens the long-lived `FetchX509SVID` stream and invokes `callback`
+    /// exactly when the agent pushes a new SVID, rather than on a fixed
+    /// timer. If the stream or the underlying channel fails, the background
+    /// task reconnects and re-opens the stream using `backoff`, resetting
+    /// the attempt counter once an SVID is delivered successfully; it never
+    /// exits on its own.
+    pub async fn watch_x509_svid<F>(&self, socket_path: String, backoff: BackoffConfig, mut callback: F) -> Result<()>
     where
         F: FnMut(X509Svid) + Send + 'static,
     {
         info!("Starting X.509 SVID watch");
 
-        // In a real implementation, this would:
-        // 1. Call the streaming Workload API
-        // 2. Process updates as they arrive
-        // 3. Call the callback for each update
+        let mut channel = self.channel.clone();
+        let svid_bundle = self.svid_bundle.clone();
+        let trust_bundles = self.trust_bundles.clone();
 
-        // Placeholder for streaming implementation
         tokio::spawn(async move {
+            let mut attempt = 0u32;
+
             loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(30)).await;
-                debug!("SVID rotation check");
-                // callback(new_svid);
+                let mut stream = match SpiffeWorkloadApiClient::new(channel.clone())
+                    .fetch_x509_svid(workload_request(X509SvidRequest {}))
+                    .await
+                {
+                    Ok(response) => response.into_inner(),
+                    Err(e) => {
+                        let delay = backoff.delay_for(attempt);
+                        warn!("Failed to open X.509 SVID stream: {}; retrying in {:?}", e, delay);
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                        channel = match Self::connect_to_agent(&socket_path).await {
+                            Ok(c) => c,
+                            Err(e) => {
+                                warn!("Reconnect to SPIRE agent failed: {}", e);
+                                continue;
+                            }
+                        };
+                        continue;
+                    }
+                };
+
+                loop {
+                    let next = match stream.next().await {
+                        Some(next) => next,
+                        None => {
+                            warn!("X.509 SVID stream ended; reconnecting");
+                            break;
+                        }
+                    };
+
+                    let response = match next {
+                        Ok(response) => response,
+                        Err(e) => {
+                            warn!("X.509 SVID stream error: {}", e);
+                            break;
+                        }
+                    };
+
+                    let (svid, bundles) = match primary_svid_from_response(response) {
+                        Ok(parsed) => parsed,
+                        Err(e) => {
+                            warn!("Failed to parse pushed X.509 SVID: {}", e);
+                            continue;
+                        }
+                    };
+
+                    debug!("Received rotated SVID for {}", svid.spiffe_id());
+
+                    {
+                        let mut bundle = svid_bundle.write().await;
+                        match bundle.as_mut() {
+                            Some(b) => b.x509_svid = Some(Arc::new(svid.clone())),
+                            None => *bundle = Some(SvidBundle::new(Some(svid.clone()))),
+                        }
+                    }
+
+                    {
+                        let store = trust_bundles.read().await;
+                        for trust_bundle in bundles {
+                            if let Err(e) = store.set_bundle(trust_bundle) {
+                                warn!("Failed to store pushed trust bundle: {}", e);
+                            }
+                        }
+                    }
+
+                    callback(svid);
+
+                    // A successfully delivered SVID means the connection is
+                    // healthy again; forget about prior failed attempts.
+                    attempt = 0;
+                }
+
+                let delay = backoff.delay_for(attempt);
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+                channel = match Self::connect_to_agent(&socket_path).await {
+                    Ok(c) => c,
+                    Err(e) => {
+                        warn!("Reconnect to SPIRE agent failed: {}", e);
+                        continue;
+                    }
+                };
             }
         });
 
@@ -124,16 +454,22 @@ impl WorkloadApiClient {
     }
 
     /// Validate a JWT token
-    pub async fn validate_jwt(&self, token: &str, _audience: &str) -> Result<SpiffeId> {
+    pub async fn validate_jwt(&self, token: &str, audience: &str) -> Result<SpiffeId> {
         if token.is_empty() {
             return Err(Error::JwtError("Token cannot be empty".into()));
         }
 
-        // In a real implementation, this would:
-        // 1. Call the Workload API ValidateJWTSVID RPC
-        // 2. Extract and return the SPIFFE ID
+        let response = self
+            .grpc_client()
+            .validate_jwtsvid(workload_request(ValidateJwtsvidRequest {
+                audience: audience.to_string(),
+                svid: token.to_string(),
+            }))
+            .await
+            .map_err(|e| Error::agent_error(format!("ValidateJWTSVID failed: {}", e)))?
+            .into_inner();
 
-        Err(Error::agent_error("Not implemented yet"))
+        SpiffeId::parse(&response.spiffe_id)
     }
 
     /// Get current SVID bundle (cached)
@@ -198,13 +534,33 @@ impl WorkloadApiClient {
     }
 
     /// Reconnect to SPIRE agent
-    pub async fn reconnect(&mut self) -> Result<()> {
+    ///
+    /// Retries with the given backoff policy until a connection succeeds;
+    /// never gives up, since a restarting agent is expected to come back.
+    pub async fn reconnect(&mut self, backoff: &BackoffConfig) -> Result<()> {
         warn!("Attempting to reconnect to SPIRE agent");
 
-        self.channel = Self::connect_to_agent(&self.socket_path).await?;
-
-        info!("Successfully reconnected to SPIRE agent");
-        Ok(())
+        let mut attempt = 0;
+        loop {
+            match Self::connect_to_agent(&self.socket_path).await {
+                Ok(channel) => {
+                    self.channel = channel;
+                    info!("Successfully reconnected to SPIRE agent after {} attempt(s)", attempt + 1);
+                    return Ok(());
+                }
+                Err(e) => {
+                    let delay = backoff.delay_for(attempt);
+                    warn!(
+                        "Reconnect attempt {} failed: {}; retrying in {:?}",
+                        attempt + 1,
+                        e,
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+            }
+        }
     }
 }
 
@@ -215,10 +571,16 @@ pub struct WorkloadApiConfig {
     pub socket_path: String,
     /// Enable automatic SVID rotation
     pub auto_rotate: bool,
-    /// Rotation check interval (seconds)
-    pub rotation_interval: u64,
+    /// Fraction of an SVID's actual validity window that may elapse before
+    /// proactive renewal is attempted; see [`crate::svid::X509Svid::rotation_deadline`]
+    pub rotation_threshold: f64,
+    /// Renewal is always attempted at least this long before an SVID
+    /// expires outright, regardless of `rotation_threshold`
+    pub pre_expiry_margin: std::time::Duration,
     /// Enable trust bundle caching
     pub cache_bundles: bool,
+    /// Backoff policy used to reconnect after agent or stream failures
+    pub backoff: BackoffConfig,
 }
 
 impl Default for WorkloadApiConfig {
@@ -226,8 +588,10 @@ impl Default for WorkloadApiConfig {
         WorkloadApiConfig {
             socket_path: DEFAULT_SOCKET_PATH.to_string(),
             auto_rotate: true,
-            rotation_interval: 300, // 5 minutes
+            rotation_threshold: 0.5,
+            pre_expiry_margin: std::time::Duration::from_secs(60),
             cache_bundles: true,
+            backoff: BackoffConfig::default(),
         }
     }
 }
@@ -237,51 +601,168 @@ pub struct ManagedWorkloadClient {
     client: Arc<WorkloadApiClient>,
     config: WorkloadApiConfig,
     shutdown: Arc<RwLock<bool>>,
+    svid_updates: watch::Sender<Option<X509Svid>>,
 }
 
 impl ManagedWorkloadClient {
     /// Create a new managed client
     pub async fn new(config: WorkloadApiConfig) -> Result<Self> {
         let client = WorkloadApiClient::new(Some(config.socket_path.clone())).await?;
+        let (svid_updates, _) = watch::channel(None);
 
         let managed = ManagedWorkloadClient {
             client: Arc::new(client),
             config,
             shutdown: Arc::new(RwLock::new(false)),
+            svid_updates,
         };
 
         if managed.config.auto_rotate {
-            managed.start_rotation_task();
+            managed.start_rotation_task().await?;
+            managed.start_proactive_refresh_task().await?;
         }
 
         Ok(managed)
     }
 
-    /// Start automatic SVID rotation task
-    fn start_rotation_task(&self) {
+    /// Start automatic SVID rotation, driven by the agent's push stream
+    ///
+    /// Subscribes to [`WorkloadApiClient::watch_x509_svid`] so the cache
+    /// updates the instant the agent rotates the SVID, rather than on a
+    /// fixed poll interval.
+    async fn start_rotation_task(&self) -> Result<()> {
+        let socket_path = self.config.socket_path.clone();
+        let backoff = self.config.backoff.clone();
+        let updates = self.svid_updates.clone();
+
+        self.client
+            .watch_x509_svid(socket_path, backoff, move |svid| {
+                info!("SVID rotated: {}", svid.spiffe_id());
+                let _ = updates.send(Some(svid));
+            })
+            .await
+    }
+
+    /// Proactively refresh the cached SVID as it nears its rotation
+    /// deadline, as a fallback in case the agent's push stream stalls
+    ///
+    /// Rather than polling on a flat interval, this sleeps until the
+    /// deadline computed from the SVID's actual validity window (see
+    /// [`WorkloadApiConfig::rotation_threshold`] and
+    /// [`WorkloadApiConfig::pre_expiry_margin`]), then calls
+    /// `fetch_x509_svid` directly and reschedules off the freshly fetched
+    /// SVID's own deadline — tightening automatically as expiry approaches.
+    async fn start_proactive_refresh_task(&self) -> Result<()> {
         let client = self.client.clone();
-        let interval = self.config.rotation_interval;
+        let rotation_threshold = self.config.rotation_threshold;
+        let pre_expiry_margin = chrono::Duration::from_std(self.config.pre_expiry_margin)
+            .unwrap_or_else(|_| chrono::Duration::seconds(60));
+        let updates = self.svid_updates.clone();
         let shutdown = self.shutdown.clone();
+        let backoff = self.config.backoff.clone();
 
-        tokio::spawn(async move {
-            let mut interval_timer = tokio::time::interval(
-                tokio::time::Duration::from_secs(interval),
-            );
+        // Seed the cache so the task has a deadline to compute against.
+        let initial = client.fetch_x509_svid().await?;
+        let _ = updates.send(Some(initial));
 
+        tokio::spawn(async move {
+            let mut attempt: u32 = 0;
             loop {
-                interval_timer.tick().await;
+                let current = updates.borrow().clone();
+                let Some(svid) = current else { break };
+
+                if *shutdown.read().await {
+                    break;
+                }
+
+                let deadline = svid.rotation_deadline(rotation_threshold, pre_expiry_margin);
+                let wait = (deadline - Utc::now())
+                    .to_std()
+                    .unwrap_or(std::time::Duration::ZERO);
+                tokio::time::sleep(wait).await;
 
                 if *shutdown.read().await {
-                    info!("Stopping rotation task");
                     break;
                 }
 
-                if let Some(bundle) = client.get_svid_bundle().await {
-                    if bundle.needs_rotation() {
-                        info!("SVID rotation needed");
-                        if let Err(e) = client.refresh_all().await {
-                            error!("Failed to rotate SVIDs: {}", e);
+                match client.fetch_x509_svid().await {
+                    Ok(fresh) => {
+                        attempt = 0;
+                        if fresh.serial_number() != svid.serial_number() {
+                            info!("Proactively rotated SVID for {}", fresh.spiffe_id());
                         }
+                        let _ = updates.send(Some(fresh));
+                    }
+                    Err(e) => {
+                        let delay = backoff.delay_for(attempt);
+                        attempt = attempt.saturating_add(1);
+                        warn!("Proactive SVID refresh failed: {} (retrying in {:?})", e, delay);
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Subscribe to SVID rotations pushed by the SPIRE agent
+    ///
+    /// The receiver's initial value is `None` until the first SVID arrives;
+    /// every subsequent rotation sends `Some(svid)`.
+    pub fn subscribe(&self) -> watch::Receiver<Option<X509Svid>> {
+        self.svid_updates.subscribe()
+    }
+
+    /// Stream every X.509 SVID rotation paired with its trust bundle
+    ///
+    /// Built on top of [`Self::subscribe`], so reconnection and backoff on
+    /// transport failure happen transparently inside the background watch
+    /// task started by `auto_rotate`; this stream only ever observes SVIDs
+    /// that were delivered successfully. A rotation whose bundle hasn't been
+    /// cached yet (e.g. the very first SVID, seeded before any bundle fetch)
+    /// is skipped rather than yielded with a stale or missing bundle.
+    pub fn watch_x509_svids(&self) -> impl futures::Stream<Item = (X509Svid, TrustBundle)> + 'static {
+        futures::stream::unfold((self.subscribe(), self.client.clone()), |(mut updates, client)| async move {
+            loop {
+                if updates.changed().await.is_err() {
+                    return None;
+                }
+
+                let Some(svid) = updates.borrow().clone() else {
+                    continue;
+                };
+
+                let Some(bundle) = client.get_trust_bundle(svid.spiffe_id().trust_domain()).await else {
+                    continue;
+                };
+
+                return Some(((svid, bundle), (updates, client)));
+            }
+        })
+    }
+
+    /// Keep an [`MtlsConfig`](crate::mtls::MtlsConfig)'s signing identity in sync with SVID rotations
+    ///
+    /// Spawns a task that calls `MtlsConfig::update_svid` every time this
+    /// client's agent push stream delivers a rotated SVID, so a long-lived
+    /// TLS acceptor/connector built from `mtls_config` rotates in place
+    /// rather than being rebuilt. Federated trust bundle rotation is wired
+    /// the same way by callers, via [`crate::BundleEndpoint::subscribe`]
+    /// and `MtlsConfig::update_bundle`.
+    pub fn sync_mtls_identity(
+        &self,
+        mtls_config: Arc<RwLock<crate::mtls::MtlsConfig>>,
+        trust_bundle: TrustBundle,
+    ) {
+        let mut updates = self.subscribe();
+        tokio::spawn(async move {
+            while updates.changed().await.is_ok() {
+                let svid = updates.borrow().clone();
+                if let Some(svid) = svid {
+                    let mut config = mtls_config.write().await;
+                    if let Err(e) = config.update_svid(&svid, &trust_bundle) {
+                        warn!("Failed to rotate mTLS identity: {}", e);
                     }
                 }
             }
@@ -305,12 +786,24 @@ impl ManagedWorkloadClient {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_normalize_socket_path() {
+        assert_eq!(
+            normalize_socket_path("unix:/tmp/spire-agent/public/api.sock"),
+            "/tmp/spire-agent/public/api.sock"
+        );
+        assert_eq!(
+            normalize_socket_path("/tmp/spire-agent/public/api.sock"),
+            "/tmp/spire-agent/public/api.sock"
+        );
+    }
+
     #[tokio::test]
     async fn test_workload_api_config() {
         let config = WorkloadApiConfig::default();
         assert_eq!(config.socket_path, DEFAULT_SOCKET_PATH);
         assert!(config.auto_rotate);
-        assert_eq!(config.rotation_interval, 300);
+        assert_eq!(config.rotation_threshold, 0.5);
     }
 
     #[tokio::test]
@@ -21,5 +21,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     tonic_build::configure()
         .build_server(false)
         .compile_protos(&protos, &["proto/spire-api-sdk/proto"])?;
+
+    // The public Workload API isn't part of the spire-api-sdk bundle above;
+    // it's vendored directly since it's the SPIFFE (not SPIRE-specific) API
+    // a workload uses to talk to its local agent.
+    tonic_build::configure()
+        .build_server(false)
+        .compile_protos(
+            &["proto/spiffe/workload/workload.proto"],
+            &["proto"],
+        )?;
     Ok(())
   }
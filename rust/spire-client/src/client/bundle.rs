@@ -1,9 +1,31 @@
-use crate::proto::spire::api::server::bundle::v1::{GetBundleRequest, bundle_client::BundleClient as GrpcBundleClient};
+use crate::proto::spire::api::server::bundle::v1::{AppendBundleRequest, GetBundleRequest, bundle_client::BundleClient as GrpcBundleClient};
 use crate::proto::spire::api::types::{Bundle, BundleMask};
-use tonic::Status;
-use tonic::transport::{Channel, ClientTlsConfig};
+use crate::svid::SvidBundle;
+use crate::tls::svid::SpiffeServerCertVerifier;
+use crate::tls::AuthorizeAny;
+use hyper_util::rt::TokioIo;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use spiffe_client::X509Svid;
+use std::sync::Arc;
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint, Uri};
+use tower::service_fn;
 use anyhow::Error;
 
+/// Errors specific to the privileged bundle-management API, distinguishing
+/// "the server said no" from ordinary transport failures
+#[derive(Debug, thiserror::Error)]
+pub enum AppendBundleError {
+  /// The server rejected the call because the presented delegate identity
+  /// isn't authorized to manage bundles
+  #[error("AppendBundle was rejected as unauthorized: {0}")]
+  Unauthorized(String),
+
+  /// Any other failure reaching or talking to the admin endpoint
+  #[error("AppendBundle failed: {0}")]
+  Transport(#[from] Error),
+}
+
 pub struct BundleClient {
   inner: GrpcBundleClient<Channel>,
 }
@@ -27,6 +49,43 @@ impl BundleClient {
     Ok(Self::new(channel))
   }
 
+  /// Connect to the SPIRE server's privileged admin API over its own Unix
+  /// domain socket (e.g. `admin_socket_path` / `SPIRE_ADMIN_ENDPOINT_SOCKET`),
+  /// presenting `client_identity` as the delegate identity for mTLS.
+  ///
+  /// `admin_bundle` is the trust bundle for the admin endpoint's own trust
+  /// domain: its `x509_authorities` become the root store the admin
+  /// server's certificate is validated against. The admin server's SVID
+  /// carries only a URI SAN, never a DNS SAN, so tonic's own
+  /// `ClientTlsConfig::domain_name` (which drives rustls' stock
+  /// hostname-based `ServerName::DnsName` check) can never accept it; this
+  /// instead drives the TLS handshake through a raw rustls `ClientConfig`
+  /// built with [`SpiffeServerCertVerifier`], the same SPIFFE-aware,
+  /// SAN-based verifier `crate::tls` uses elsewhere, so a socket pointed at
+  /// an impostor admin API still fails the handshake.
+  ///
+  /// Distinct from [`Self::connect`], which dials the public API endpoint
+  /// and carries no client identity.
+  pub async fn connect_admin(socket_path: String, client_identity: X509Svid, admin_bundle: Bundle) -> Result<Self, Error> {
+    let (connector, server_name) = build_admin_tls_connector(&client_identity, &admin_bundle)?;
+
+    let endpoint = Endpoint::try_from("http://[::]:0")?;
+    let channel = endpoint
+      .connect_with_connector(service_fn(move |_: Uri| {
+        let socket_path = socket_path.clone();
+        let connector = connector.clone();
+        let server_name = server_name.clone();
+        async move {
+          let stream = tokio::net::UnixStream::connect(socket_path).await?;
+          let tls_stream = connector.connect(server_name, stream).await?;
+          Ok::<_, std::io::Error>(TokioIo::new(tls_stream))
+        }
+      }))
+      .await?;
+
+    Ok(Self::new(channel))
+  }
+
   // 認証なしで呼べるAPI
   pub async fn get_bundle(&mut self) -> Result<Bundle, Error> {
     let request = tonic::Request::new(GetBundleRequest {
@@ -55,110 +114,206 @@ impl BundleClient {
         Ok(bundle)
   }
 
-    // 管理者権限が必要なAPI
-  pub async fn append_bundle(&mut self, _bundle: Bundle) -> Result<Bundle, Status> {
-    // 認証設定を含む実装
-    unimplemented!("BundleClient::append_bundle is not yet implemented")
+  // 管理者権限が必要なAPI: 呼び出し元はconnect_adminで確立した委任IDを提示済みであること
+  pub async fn append_bundle(&mut self, bundle: Bundle) -> Result<Bundle, AppendBundleError> {
+    let request = tonic::Request::new(AppendBundleRequest {
+      bundle: Some(bundle),
+      input_mask: Some(BundleMask {
+        x509_authorities: true,
+        jwt_authorities: true,
+        refresh_hint: true,
+        sequence_number: true,
+      }),
+    });
+
+    let response = self.inner.append_bundle(request).await.map_err(map_append_bundle_status)?;
+
+    Ok(response.into_inner())
   }
 }
 
+/// Map an `AppendBundle` gRPC status to [`AppendBundleError`], distinguishing
+/// a server-side authorization rejection from any other transport failure
+fn map_append_bundle_status(status: tonic::Status) -> AppendBundleError {
+  if status.code() == tonic::Code::PermissionDenied {
+    AppendBundleError::Unauthorized(status.message().to_string())
+  } else {
+    AppendBundleError::Transport(anyhow::anyhow!(
+      "AppendBundle failed: code={}, message={}",
+      status.code(),
+      status.message()
+    ))
+  }
+}
+
+/// Build the raw rustls TLS connector used to dial the admin endpoint
+///
+/// `admin_bundle`'s `x509_authorities` become the root store the admin
+/// server's certificate is validated against, via [`SpiffeServerCertVerifier`]
+/// rather than tonic's own `ClientTlsConfig`: the admin SVID carries only a
+/// URI SAN, so rustls' stock hostname-based `ServerName::DnsName` check
+/// (what `ClientTlsConfig::domain_name` drives) can never accept it. The
+/// returned `ServerName` only needs to parse — the verifier ignores it and
+/// checks the peer's SPIFFE URI SAN instead.
+fn build_admin_tls_connector(
+  client_identity: &X509Svid,
+  admin_bundle: &Bundle,
+) -> Result<(TlsConnector, ServerName<'static>), Error> {
+  let ca_certs: Vec<CertificateDer<'static>> = admin_bundle
+    .x509_authorities
+    .iter()
+    .map(|auth| CertificateDer::from(auth.asn1.clone()))
+    .collect();
+  let verifier = Arc::new(SpiffeServerCertVerifier::new(
+    SvidBundle::new(admin_bundle.trust_domain.clone(), ca_certs),
+    Arc::new(AuthorizeAny),
+  ));
+
+  let cert_chain: Vec<CertificateDer<'static>> = client_identity
+    .cert_chain()
+    .iter()
+    .map(|der| CertificateDer::from(der.clone()))
+    .collect();
+  let private_key = PrivateKeyDer::try_from(client_identity.private_key().to_vec())
+    .map_err(|_| anyhow::anyhow!("Failed to parse admin client private key"))?;
+
+  let tls_config = Arc::new(
+    rustls::ClientConfig::builder()
+      .dangerous()
+      .with_custom_certificate_verifier(verifier)
+      .with_client_auth_cert(cert_chain, private_key)?,
+  );
+
+  let server_name = ServerName::try_from(admin_bundle.trust_domain.clone())
+    .map_err(|_| anyhow::anyhow!("Invalid admin trust domain: {}", admin_bundle.trust_domain))?;
+
+  Ok((TlsConnector::from(tls_config), server_name))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::proto::spire::api::types::{X509Certificate, JwtKey};
-    use tonic::transport::Channel;
+    use crate::proto::spire::api::types::X509Certificate;
+    use rcgen::{CertificateParams, Ia5String, KeyPair, SanType};
 
-    #[tokio::test]
-    async fn test_bundle_client_new() {
-        let endpoint = "spire-server.example.com:8081";
-        let channel = Channel::from_shared(endpoint).unwrap().connect_lazy();
-        let client = BundleClient::new(channel);
-        
-        // クライアントが正常に作成されることを確認
-        assert!(std::mem::size_of_val(&client) > 0);
+    fn client_identity_fixture() -> X509Svid {
+        let cert = rcgen::generate_simple_self_signed(vec!["admin-client.example.org".to_string()]).expect("self-signed cert");
+        X509Svid::new(
+            spiffe_client::SpiffeId::new("example.org", "/admin-client").unwrap(),
+            vec![cert.cert.der().to_vec()],
+            cert.key_pair.serialize_der(),
+            chrono::Utc::now() - chrono::Duration::seconds(1),
+            chrono::Utc::now() + chrono::Duration::hours(1),
+            "1".to_string(),
+        )
+        .expect("valid SVID fixture")
     }
 
     #[test]
-    fn test_bundle_validation_empty() {
-        let empty_bundle = Bundle {
-            trust_domain: "example.org".to_string(),
-            x509_authorities: vec![],
-            jwt_authorities: vec![],
-            refresh_hint: 0,
-            sequence_number: 0,
-        };
+    fn test_append_bundle_error_distinguishes_unauthorized() {
+        // 権限エラーは輸送エラーと区別できる
+        let unauthorized = AppendBundleError::Unauthorized("not a registered delegate".to_string());
+        assert!(matches!(unauthorized, AppendBundleError::Unauthorized(_)));
 
-        // 空のバンドルは無効
-        assert!(empty_bundle.x509_authorities.is_empty() && empty_bundle.jwt_authorities.is_empty());
+        let transport: AppendBundleError = anyhow::anyhow!("connection refused").into();
+        assert!(matches!(transport, AppendBundleError::Transport(_)));
     }
 
     #[test]
-    fn test_bundle_validation_valid() {
-        let valid_bundle = Bundle {
-            trust_domain: "example.org".to_string(),
-            x509_authorities: vec![X509Certificate {
-                asn1: b"mock-cert".to_vec(),
-                tainted: false,
-            }],
-            jwt_authorities: vec![JwtKey {
-                public_key: b"mock-public-key".to_vec(),
-                key_id: "key-1".to_string(),
-                expires_at: 0,
-                tainted: false,
-            }],
-            refresh_hint: 3600,
-            sequence_number: 1,
-        };
-
-        // 有効なバンドル
-        assert!(!valid_bundle.x509_authorities.is_empty() || !valid_bundle.jwt_authorities.is_empty());
-        assert_eq!(valid_bundle.trust_domain, "example.org");
-        assert_eq!(valid_bundle.sequence_number, 1);
-        assert_eq!(valid_bundle.refresh_hint, 3600);
+    fn test_map_append_bundle_status_maps_permission_denied_to_unauthorized() {
+        let status = tonic::Status::permission_denied("not a registered delegate");
+        let error = map_append_bundle_status(status);
+        assert!(matches!(error, AppendBundleError::Unauthorized(msg) if msg == "not a registered delegate"));
     }
 
     #[test]
-    fn test_get_bundle_request_creation() {
-        let request = GetBundleRequest {
-            output_mask: Some(BundleMask {
-                x509_authorities: true,
-                jwt_authorities: true,
-                refresh_hint: true,
-                sequence_number: true,
-            }),
-        };
-
-        assert!(request.output_mask.is_some());
-        let mask = request.output_mask.unwrap();
-        assert!(mask.x509_authorities);
-        assert!(mask.jwt_authorities);
-        assert!(mask.refresh_hint);
-        assert!(mask.sequence_number);
+    fn test_map_append_bundle_status_maps_other_codes_to_transport() {
+        let status = tonic::Status::unavailable("admin endpoint unreachable");
+        let error = map_append_bundle_status(status);
+        assert!(matches!(error, AppendBundleError::Transport(_)));
     }
 
-    #[test]
-    fn test_x509_certificate_structure() {
-        let cert = X509Certificate {
-            asn1: b"test-cert".to_vec(),
-            tainted: false,
+    /// `connect_admin` actually dials the admin UDS over the mTLS config it
+    /// built from `admin_bundle`, rather than stopping after building the
+    /// config: pointed at a socket that doesn't exist, it must still surface
+    /// the dial failure instead of hanging or silently succeeding.
+    #[tokio::test]
+    async fn test_connect_admin_surfaces_a_dial_failure_for_a_missing_socket() {
+        let cert = rcgen::generate_simple_self_signed(vec!["admin.example.org".to_string()]).expect("self-signed cert");
+        let leaf_der = cert.cert.der().to_vec();
+
+        let admin_bundle = Bundle {
+            trust_domain: "admin.example.org".to_string(),
+            x509_authorities: vec![X509Certificate { asn1: leaf_der, tainted: false }],
+            jwt_authorities: vec![],
+            refresh_hint: 0,
+            sequence_number: 0,
         };
 
-        assert_eq!(cert.asn1, b"test-cert");
-        assert!(!cert.tainted);
+        let result = BundleClient::connect_admin(
+            "/tmp/spire-sandbox-admin-does-not-exist.sock".to_string(),
+            client_identity_fixture(),
+            admin_bundle,
+        )
+        .await;
+
+        assert!(result.is_err());
     }
 
-    #[test]
-    fn test_jwt_key_structure() {
-        let jwt_key = JwtKey {
-            public_key: b"test-public-key".to_vec(),
-            key_id: "test-key-id".to_string(),
-            expires_at: 1234567890,
-            tainted: false,
+    /// The admin SVID carries only a URI SAN, never a DNS SAN, so the TLS
+    /// handshake has to be driven by `SpiffeServerCertVerifier`'s SAN-based
+    /// check rather than tonic's stock hostname-based verification (which
+    /// would reject this certificate as `NotValidForNameContext` even though
+    /// it's perfectly legitimate). Drive an actual handshake against a
+    /// listening socket presenting such a certificate to prove it.
+    #[tokio::test]
+    async fn test_admin_tls_connector_accepts_a_spiffe_san_only_certificate() {
+        let key_pair = KeyPair::generate().expect("key pair");
+        let mut params = CertificateParams::new(Vec::<String>::new()).expect("empty SAN params");
+        params.subject_alt_names = vec![SanType::URI(
+            Ia5String::try_from("spiffe://admin.example.org/admin").expect("valid IA5 string"),
+        )];
+        let admin_cert = params.self_signed(&key_pair).expect("self-signed admin cert");
+        let cert_der = admin_cert.der().clone();
+
+        let server_config = Arc::new(
+            rustls::ServerConfig::builder()
+                .with_no_client_auth()
+                .with_single_cert(
+                    vec![cert_der.clone()],
+                    PrivateKeyDer::try_from(key_pair.serialize_der()).expect("valid private key"),
+                )
+                .expect("valid server TLS config"),
+        );
+
+        let socket_path = "/tmp/spire-sandbox-admin-tls-test.sock".to_string();
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = tokio::net::UnixListener::bind(&socket_path).expect("bind admin socket");
+
+        let accept_task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.expect("accept connection");
+            tokio_rustls::TlsAcceptor::from(server_config)
+                .accept(stream)
+                .await
+                .expect("server-side TLS handshake");
+        });
+
+        let admin_bundle = Bundle {
+            trust_domain: "admin.example.org".to_string(),
+            x509_authorities: vec![X509Certificate { asn1: cert_der.to_vec(), tainted: false }],
+            jwt_authorities: vec![],
+            refresh_hint: 0,
+            sequence_number: 0,
         };
 
-        assert_eq!(jwt_key.public_key, b"test-public-key");
-        assert_eq!(jwt_key.key_id, "test-key-id");
-        assert_eq!(jwt_key.expires_at, 1234567890);
-        assert!(!jwt_key.tainted);
+        let (connector, server_name) =
+            build_admin_tls_connector(&client_identity_fixture(), &admin_bundle).expect("valid admin TLS connector");
+        let stream = tokio::net::UnixStream::connect(&socket_path).await.expect("dial admin socket");
+        let result = connector.connect(server_name, stream).await;
+
+        accept_task.await.expect("server task");
+        let _ = std::fs::remove_file(&socket_path);
+
+        assert!(result.is_ok(), "handshake against a SPIFFE URI-SAN-only cert should succeed: {:?}", result.err());
     }
 }
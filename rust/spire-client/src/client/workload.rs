@@ -0,0 +1,223 @@
+//! Workload API client: the SPIFFE Workload API a SPIRE agent exposes on its
+//! public Unix domain socket, letting a workload fetch its own identity.
+//!
+//! Distinct from [`crate::client::bundle::BundleClient`], which dials the
+//! SPIRE server's privileged admin API instead of the local agent.
+
+use crate::proto::spiffe::workload::{
+  spiffe_workload_api_client::SpiffeWorkloadApiClient, JwtsvidRequest, X509BundlesRequest,
+  X509Svid as ProtoX509Svid, X509SvidRequest,
+};
+use crate::svid::{JwtSvid, SvidBundle, X509Svid};
+use crate::tls::SpiffeId;
+use anyhow::Error;
+use futures::{Stream, StreamExt};
+use hyper_util::rt::TokioIo;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer};
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// Default SPIRE agent Workload API socket path
+pub const DEFAULT_SOCKET_PATH: &str = "/tmp/spire-agent/public/api.sock";
+
+/// Name of the metadata header the Workload API requires on every call, per
+/// the SPIFFE Workload Endpoint spec
+const WORKLOAD_API_SECURITY_HEADER: &str = "workload.spiffe.io";
+
+/// Wrap `message` in a gRPC request carrying the mandatory
+/// `workload.spiffe.io: true` security header
+fn workload_request<T>(message: T) -> tonic::Request<T> {
+  let mut request = tonic::Request::new(message);
+  request.metadata_mut().insert(
+    WORKLOAD_API_SECURITY_HEADER,
+    tonic::metadata::MetadataValue::from_static("true"),
+  );
+  request
+}
+
+/// Split a chain of concatenated DER certificates (as delivered by the
+/// Workload API, leaf first) into its individual certificates
+fn split_der_chain(chain: &[u8]) -> Result<Vec<CertificateDer<'static>>, Error> {
+  let mut certs = Vec::new();
+  let mut rest = chain;
+
+  while !rest.is_empty() {
+    let (remainder, cert) = X509Certificate::from_der(rest)
+      .map_err(|e| anyhow::anyhow!("Failed to parse certificate in chain: {}", e))?;
+    let consumed = rest.len() - remainder.len();
+    certs.push(CertificateDer::from(rest[..consumed].to_vec()));
+    rest = remainder;
+  }
+
+  if certs.is_empty() {
+    return Err(anyhow::anyhow!("Certificate chain is empty"));
+  }
+
+  Ok(certs)
+}
+
+/// Convert a single `X509SVID` protobuf message into our domain type, plus
+/// the trust bundles it carries (own bundle + any federated bundles)
+fn convert_x509_svid(svid: &ProtoX509Svid) -> Result<(X509Svid, Vec<SvidBundle>), Error> {
+  let spiffe_id = SpiffeId::parse(&svid.spiffe_id)?;
+  let cert_chain = split_der_chain(&svid.x509_svid)?;
+  let private_key = PrivateKeyDer::try_from(svid.x509_svid_key.clone())
+    .map_err(|e| anyhow::anyhow!("Invalid SVID private key: {}", e))?;
+
+  let x509_svid = X509Svid::new(spiffe_id.clone(), cert_chain, private_key);
+
+  let mut bundles = Vec::new();
+  if !svid.bundle.is_empty() {
+    bundles.push(SvidBundle::new(spiffe_id.trust_domain().to_string(), split_der_chain(&svid.bundle)?));
+  }
+  for (trust_domain, der) in &svid.federated_bundles {
+    bundles.push(SvidBundle::new(trust_domain.clone(), split_der_chain(der)?));
+  }
+
+  Ok((x509_svid, bundles))
+}
+
+/// Workload API client: fetches X.509 and JWT SVIDs, and trust bundles, from
+/// the SPIRE agent a workload is colocated with
+pub struct WorkloadApiClient {
+  channel: Channel,
+}
+
+impl WorkloadApiClient {
+  /// Connect to the agent's Workload API over its public Unix domain socket
+  pub async fn connect(socket_path: impl Into<String>) -> Result<Self, Error> {
+    let socket_path = socket_path.into();
+
+    // The URI is never actually dialed; the connector below always redirects
+    // to the Unix socket path captured in the closure.
+    let endpoint = Endpoint::try_from("http://[::]:0")?;
+    let channel = endpoint
+      .connect_with_connector(service_fn(move |_: Uri| {
+        let socket_path = socket_path.clone();
+        async move {
+          let stream = tokio::net::UnixStream::connect(socket_path).await?;
+          Ok::<_, std::io::Error>(TokioIo::new(stream))
+        }
+      }))
+      .await?;
+
+    Ok(Self { channel })
+  }
+
+  fn grpc_client(&self) -> SpiffeWorkloadApiClient<Channel> {
+    SpiffeWorkloadApiClient::new(self.channel.clone())
+  }
+
+  /// Fetch the calling workload's X.509 SVID
+  ///
+  /// Opens the server-streaming `FetchX509SVID` RPC and returns the first
+  /// message's primary SVID; callers that want every subsequent rotation
+  /// should use [`Self::watch_x509_context`] instead.
+  pub async fn fetch_x509_svid(&self) -> Result<X509Svid, Error> {
+    let mut stream = self
+      .grpc_client()
+      .fetch_x509_svid(workload_request(X509SvidRequest {}))
+      .await?
+      .into_inner();
+
+    let response = stream
+      .message()
+      .await?
+      .ok_or_else(|| anyhow::anyhow!("Agent closed the X509SVID stream with no response"))?;
+
+    let svid = response
+      .svids
+      .first()
+      .ok_or_else(|| anyhow::anyhow!("Agent returned an X509SVIDResponse with no SVIDs"))?;
+
+    let (svid, _bundles) = convert_x509_svid(svid)?;
+    Ok(svid)
+  }
+
+  /// Fetch a JWT-SVID for the given audience
+  pub async fn fetch_jwt_svid(&self, audience: Vec<String>) -> Result<JwtSvid, Error> {
+    if audience.is_empty() {
+      return Err(anyhow::anyhow!("Audience cannot be empty"));
+    }
+
+    let response = self
+      .grpc_client()
+      .fetch_jwtsvid(workload_request(JwtsvidRequest {
+        audience,
+        spiffe_id: String::new(),
+      }))
+      .await?
+      .into_inner();
+
+    let svid = response
+      .svids
+      .first()
+      .ok_or_else(|| anyhow::anyhow!("Agent returned a JWTSVIDResponse with no SVIDs"))?;
+
+    let spiffe_id = SpiffeId::parse(&svid.spiffe_id)?;
+    Ok(JwtSvid::new(spiffe_id, svid.svid.clone()))
+  }
+
+  /// Fetch the X.509 trust bundles for every trust domain the agent knows
+  /// about, keyed implicitly by each [`SvidBundle::trust_domain`]
+  pub async fn fetch_x509_bundles(&self) -> Result<Vec<SvidBundle>, Error> {
+    let mut stream = self
+      .grpc_client()
+      .fetch_x509_bundles(workload_request(X509BundlesRequest {}))
+      .await?
+      .into_inner();
+
+    let response = stream
+      .message()
+      .await?
+      .ok_or_else(|| anyhow::anyhow!("Agent closed the X509Bundles stream with no response"))?;
+
+    response
+      .bundles
+      .iter()
+      .map(|(trust_domain, der)| Ok(SvidBundle::new(trust_domain.clone(), split_der_chain(der)?)))
+      .collect()
+  }
+
+  /// Stream every X.509 SVID the agent pushes, paired with the trust
+  /// bundles delivered alongside it, starting with the one the agent holds
+  /// right now and continuing across every later rotation
+  pub async fn watch_x509_context(
+    &self,
+  ) -> Result<impl Stream<Item = Result<(X509Svid, Vec<SvidBundle>), Error>>, Error> {
+    let stream = self
+      .grpc_client()
+      .fetch_x509_svid(workload_request(X509SvidRequest {}))
+      .await?
+      .into_inner();
+
+    Ok(stream.map(|message| {
+      let response = message.map_err(|e| anyhow::anyhow!("X509SVID stream error: {}", e))?;
+      let svid = response
+        .svids
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("Agent pushed an X509SVIDResponse with no SVIDs"))?;
+      convert_x509_svid(svid)
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_der_chain_rejects_empty_input() {
+    assert!(split_der_chain(&[]).is_err());
+  }
+
+  #[tokio::test]
+  async fn test_fetch_jwt_svid_rejects_empty_audience() {
+    let channel = Channel::from_shared("http://[::]:0").unwrap().connect_lazy();
+    let client = WorkloadApiClient { channel };
+
+    let result = client.fetch_jwt_svid(vec![]).await;
+    assert!(result.is_err());
+  }
+}
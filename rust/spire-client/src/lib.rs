@@ -13,9 +13,18 @@ pub mod proto {
       }
     }
   }
+
+  pub mod spiffe {
+    pub mod workload {
+      tonic::include_proto!("spiffe.workload");
+    }
+  }
 }
 
 pub mod client;
 pub use client::bundle::BundleClient;
+pub use client::workload::WorkloadApiClient;
 pub mod error;
 pub mod transport;
+pub mod tls;
+pub mod svid;
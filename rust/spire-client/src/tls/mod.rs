@@ -1,10 +1,239 @@
-// SPIFFE準拠の証明書検証器
-pub struct SpiffeVerifier {
+//! Handshake-time SPIFFE certificate verification for client-side mTLS
+//!
+//! [`SpiffeVerifier`] is a genuine `rustls::client::danger::ServerCertVerifier`:
+//! chain validation and SPIFFE ID authorization both happen inside
+//! `verify_server_cert`, so an unauthorized peer fails the handshake instead
+//! of being caught only after the connection is already established.
+
+pub mod svid;
+
+use crate::error::SpiffeError;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::{DigitallySignedStruct, Error as TlsError, RootCertStore, SignatureScheme};
+use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
+use std::sync::Arc;
+use webpki::{EndEntityCert, KeyUsage};
+use x509_parser::prelude::*;
+
+/// Validate `end_entity`'s chain against `roots`, with no hostname/SAN check
+///
+/// SPIFFE SVIDs carry only a URI SAN, never a DNS/IP SAN, so
+/// `rustls::client::WebPkiServerVerifier` (which always checks the leaf
+/// against a `ServerName`) can never accept one — there is no name to match.
+/// This builds the `webpki` end-entity cert directly and calls
+/// `verify_for_usage` with no server name, leaving identity binding to the
+/// caller's own SPIFFE ID check.
+pub(crate) fn verify_chain_for_server_auth(
+  end_entity: &CertificateDer,
+  intermediates: &[CertificateDer],
+  roots: &RootCertStore,
+  now: UnixTime,
+) -> Result<(), TlsError> {
+  let cert = EndEntityCert::try_from(end_entity)
+    .map_err(|e| TlsError::General(format!("Invalid end-entity certificate: {:?}", e)))?;
+
+  let sig_algs = rustls::crypto::ring::default_provider().signature_verification_algorithms;
+
+  cert
+    .verify_for_usage(sig_algs.all, &roots.roots, intermediates, now, KeyUsage::server_auth(), None)
+    .map(|_| ())
+    .map_err(|e| TlsError::General(format!("Certificate chain validation failed: {:?}", e)))
+}
+
+/// Parsed `spiffe://trust-domain/path` identity extracted from a leaf
+/// certificate's URI SAN
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpiffeId {
   trust_domain: String,
-  bundle: Bundle, // SPIREから取得したbundle
+  path: String,
+}
+
+impl SpiffeId {
+  /// Parse a `spiffe://` URI into its trust domain and path
+  pub fn parse(uri: &str) -> Result<Self, SpiffeError> {
+    let rest = uri
+      .strip_prefix("spiffe://")
+      .ok_or_else(|| SpiffeError::InvalidSpiffeId(uri.to_string()))?;
+
+    let mut parts = rest.splitn(2, '/');
+    let trust_domain = parts
+      .next()
+      .filter(|s| !s.is_empty())
+      .ok_or_else(|| SpiffeError::InvalidSpiffeId(uri.to_string()))?;
+    let path = parts.next().unwrap_or("");
+
+    Ok(Self {
+      trust_domain: trust_domain.to_string(),
+      path: format!("/{}", path),
+    })
+  }
+
+  /// The trust domain component, e.g. `example.org`
+  pub fn trust_domain(&self) -> &str {
+    &self.trust_domain
+  }
+
+  /// The workload path component, e.g. `/ns/payments/sa/web`
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+}
+
+/// Policy deciding whether a verified peer's SPIFFE ID should be trusted
+pub trait SpiffeIdAuthorizer: std::fmt::Debug + Send + Sync {
+  /// Returns `true` if `id` is allowed to be trusted
+  fn authorize(&self, id: &SpiffeId) -> bool;
+}
+
+/// Authorize any peer presenting a well-formed SPIFFE ID
+#[derive(Debug, Default)]
+pub struct AuthorizeAny;
+
+impl SpiffeIdAuthorizer for AuthorizeAny {
+  fn authorize(&self, _id: &SpiffeId) -> bool {
+    true
+  }
+}
+
+/// Authorize any peer belonging to the given trust domain
+#[derive(Debug)]
+pub struct AuthorizeMemberOf(pub String);
+
+impl SpiffeIdAuthorizer for AuthorizeMemberOf {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    id.trust_domain() == self.0
+  }
 }
 
-// 検証トレイト
-pub trait CertificateVerifier {
-  fn verify_server_cert(&self, cert: &Certificate) -> Result<SpiffeId, Error>;
+/// Authorize only peers whose ID exactly matches
+#[derive(Debug)]
+pub struct AuthorizeId(pub SpiffeId);
+
+impl SpiffeIdAuthorizer for AuthorizeId {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    id == &self.0
+  }
+}
+
+/// Client-side `ServerCertVerifier` that enforces SPIFFE policy during the
+/// TLS handshake
+///
+/// Holds the trust bundle as a set of trust-anchor DER certs and a pluggable
+/// [`SpiffeIdAuthorizer`], so callers can express "accept anything in trust
+/// domain X" ([`AuthorizeMemberOf`]) or an exact-ID policy ([`AuthorizeId`])
+/// uniformly.
+pub struct SpiffeVerifier {
+  roots: RootCertStore,
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SpiffeVerifier").finish_non_exhaustive()
+  }
+}
+
+impl SpiffeVerifier {
+  /// Build a verifier trusting `trust_anchors` (DER-encoded CA certs from the
+  /// trust bundle) and enforcing `authorizer` against the peer's SPIFFE ID
+  pub fn new(
+    trust_anchors: Vec<CertificateDer<'static>>,
+    authorizer: Arc<dyn SpiffeIdAuthorizer>,
+  ) -> Result<Self, TlsError> {
+    let mut roots = RootCertStore::empty();
+    for anchor in trust_anchors {
+      roots
+        .add(anchor)
+        .map_err(|e| TlsError::General(format!("Invalid trust anchor: {}", e)))?;
+    }
+    Ok(Self { roots, authorizer })
+  }
+
+  fn webpki_verifier(&self) -> Result<Arc<rustls::client::WebPkiServerVerifier>, TlsError> {
+    rustls::client::WebPkiServerVerifier::builder(Arc::new(self.roots.clone()))
+      .build()
+      .map_err(|e| TlsError::General(format!("Failed to build chain verifier: {}", e)))
+  }
+}
+
+impl ServerCertVerifier for SpiffeVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    server_name: &ServerName,
+    ocsp_response: &[u8],
+    now: UnixTime,
+  ) -> Result<ServerCertVerified, TlsError> {
+    // Chain + time validation only, with no hostname check; SPIFFE SVIDs
+    // carry no DNS name, so identity binding happens below against the URI
+    // SAN instead.
+    let _ = (server_name, ocsp_response);
+    verify_chain_for_server_auth(end_entity, intermediates, &self.roots, now)?;
+
+    let spiffe_id = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
+    if !self.authorizer.authorize(&spiffe_id) {
+      return Err(TlsError::General(format!(
+        "SPIFFE ID not authorized: spiffe://{}{}",
+        spiffe_id.trust_domain(),
+        spiffe_id.path()
+      )));
+    }
+
+    Ok(ServerCertVerified::assertion())
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, TlsError> {
+    self.webpki_verifier()?.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<HandshakeSignatureValid, TlsError> {
+    self.webpki_verifier()?.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    vec![
+      SignatureScheme::RSA_PSS_SHA256,
+      SignatureScheme::RSA_PSS_SHA384,
+      SignatureScheme::RSA_PSS_SHA512,
+      SignatureScheme::ECDSA_NISTP256_SHA256,
+      SignatureScheme::ECDSA_NISTP384_SHA384,
+    ]
+  }
+}
+
+const SAN_OID: der_parser::oid::Oid<'static> = der_parser::oid!(2.5.29.17);
+
+fn extract_spiffe_id(cert: &CertificateDer) -> Result<SpiffeId, SpiffeError> {
+  let (_, cert) = X509Certificate::from_der(cert.as_ref())
+    .map_err(|_| SpiffeError::ValidationError("Failed to parse certificate".to_string()))?;
+
+  for ext in cert.extensions() {
+    if ext.oid == SAN_OID {
+      let san = SubjectAlternativeName::from_der(&ext.value)
+        .map_err(|_| SpiffeError::ValidationError("Failed to parse SAN".to_string()))?;
+
+      for name in &san.1.general_names {
+        if let GeneralName::URI(uri) = name {
+          if uri.starts_with("spiffe://") {
+            return SpiffeId::parse(uri);
+          }
+        }
+      }
+    }
+  }
+
+  Err(SpiffeError::ValidationError(
+    "No SPIFFE ID found in certificate".to_string(),
+  ))
 }
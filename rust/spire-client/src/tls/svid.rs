@@ -0,0 +1,248 @@
+//! SPIFFE certificate verifiers and a cert resolver driven by [`X509Svid`]/[`SvidBundle`]
+//! instead of raw DER vectors, so an `X509Svid` can drive both ends of a
+//! connection with the same materials.
+
+use super::{extract_spiffe_id, verify_chain_for_server_auth, SpiffeIdAuthorizer};
+use crate::svid::{SvidBundle, X509Svid};
+use rustls::client::danger::{HandshakeSignatureValid as ClientSigValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::ResolvesClientCert;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::server::{ClientHello, ResolvesServerCert};
+use rustls::sign::CertifiedKey;
+use rustls::{DigitallySignedStruct, DistinguishedName, Error as TlsError, SignatureScheme};
+use std::sync::Arc;
+
+/// Client-side `ServerCertVerifier` driven by a [`SvidBundle`] instead of a
+/// bare list of trust anchors
+pub struct SpiffeServerCertVerifier {
+  bundle: SvidBundle,
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeServerCertVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SpiffeServerCertVerifier").finish_non_exhaustive()
+  }
+}
+
+impl SpiffeServerCertVerifier {
+  /// Build a verifier trusting `bundle`'s anchors and enforcing `authorizer`
+  pub fn new(bundle: SvidBundle, authorizer: Arc<dyn SpiffeIdAuthorizer>) -> Self {
+    Self { bundle, authorizer }
+  }
+
+  fn webpki_verifier(&self) -> Result<Arc<rustls::client::WebPkiServerVerifier>, TlsError> {
+    let roots = self
+      .bundle
+      .root_store()
+      .map_err(|e| TlsError::General(format!("Invalid trust bundle: {}", e)))?;
+    rustls::client::WebPkiServerVerifier::builder(Arc::new(roots))
+      .build()
+      .map_err(|e| TlsError::General(format!("Failed to build chain verifier: {}", e)))
+  }
+}
+
+impl ServerCertVerifier for SpiffeServerCertVerifier {
+  fn verify_server_cert(
+    &self,
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    server_name: &ServerName,
+    ocsp_response: &[u8],
+    now: UnixTime,
+  ) -> Result<ServerCertVerified, TlsError> {
+    // Chain validation only, with no hostname/SAN check; SPIFFE SVIDs carry
+    // no DNS name, so identity binding happens below against the URI SAN.
+    let _ = (server_name, ocsp_response);
+    let roots = self
+      .bundle
+      .root_store()
+      .map_err(|e| TlsError::General(format!("Invalid trust bundle: {}", e)))?;
+    verify_chain_for_server_auth(end_entity, intermediates, &roots, now)?;
+
+    let spiffe_id = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
+    if spiffe_id.trust_domain() != self.bundle.trust_domain() {
+      return Err(TlsError::General(format!(
+        "Leaf trust domain '{}' does not match bundle trust domain '{}'",
+        spiffe_id.trust_domain(),
+        self.bundle.trust_domain()
+      )));
+    }
+    if !self.authorizer.authorize(&spiffe_id) {
+      return Err(TlsError::General(format!(
+        "SPIFFE ID not authorized: spiffe://{}{}",
+        spiffe_id.trust_domain(),
+        spiffe_id.path()
+      )));
+    }
+
+    Ok(ServerCertVerified::assertion())
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<ClientSigValid, TlsError> {
+    self.webpki_verifier()?.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<ClientSigValid, TlsError> {
+    self.webpki_verifier()?.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    default_verify_schemes()
+  }
+}
+
+/// Server-side `ClientCertVerifier` driven by a [`SvidBundle`] instead of a
+/// bare list of trust anchors
+pub struct SpiffeClientCertVerifier {
+  bundle: SvidBundle,
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeClientCertVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SpiffeClientCertVerifier").finish_non_exhaustive()
+  }
+}
+
+impl SpiffeClientCertVerifier {
+  /// Build a verifier trusting `bundle`'s anchors and enforcing `authorizer`
+  pub fn new(bundle: SvidBundle, authorizer: Arc<dyn SpiffeIdAuthorizer>) -> Self {
+    Self { bundle, authorizer }
+  }
+
+  fn webpki_verifier(&self) -> Result<Arc<dyn ClientCertVerifier>, TlsError> {
+    let roots = self
+      .bundle
+      .root_store()
+      .map_err(|e| TlsError::General(format!("Invalid trust bundle: {}", e)))?;
+    rustls::server::WebPkiClientVerifier::builder(Arc::new(roots))
+      .build()
+      .map_err(|e| TlsError::General(format!("Failed to build client chain verifier: {}", e)))
+  }
+}
+
+impl ClientCertVerifier for SpiffeClientCertVerifier {
+  fn offer_client_auth(&self) -> bool {
+    true
+  }
+
+  fn client_auth_mandatory(&self) -> bool {
+    true
+  }
+
+  fn root_hint_subjects(&self) -> &[DistinguishedName] {
+    &[]
+  }
+
+  fn verify_client_cert(
+    &self,
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    now: UnixTime,
+  ) -> Result<ClientCertVerified, TlsError> {
+    let spiffe_id = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
+    if spiffe_id.trust_domain() != self.bundle.trust_domain() {
+      return Err(TlsError::General(format!(
+        "Leaf trust domain '{}' does not match bundle trust domain '{}'",
+        spiffe_id.trust_domain(),
+        self.bundle.trust_domain()
+      )));
+    }
+    if !self.authorizer.authorize(&spiffe_id) {
+      return Err(TlsError::General(format!(
+        "SPIFFE ID not authorized: spiffe://{}{}",
+        spiffe_id.trust_domain(),
+        spiffe_id.path()
+      )));
+    }
+
+    self.webpki_verifier()?.verify_client_cert(end_entity, intermediates, now)
+  }
+
+  fn verify_tls12_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<ClientSigValid, TlsError> {
+    self.webpki_verifier()?.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(
+    &self,
+    message: &[u8],
+    cert: &CertificateDer<'_>,
+    dss: &DigitallySignedStruct,
+  ) -> Result<ClientSigValid, TlsError> {
+    self.webpki_verifier()?.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    default_verify_schemes()
+  }
+}
+
+fn default_verify_schemes() -> Vec<SignatureScheme> {
+  vec![
+    SignatureScheme::RSA_PSS_SHA256,
+    SignatureScheme::RSA_PSS_SHA384,
+    SignatureScheme::RSA_PSS_SHA512,
+    SignatureScheme::ECDSA_NISTP256_SHA256,
+    SignatureScheme::ECDSA_NISTP384_SHA384,
+  ]
+}
+
+/// Resolves the same [`X509Svid`] for both ends of a connection, so a single
+/// identity can back a `ClientConfig`'s client-auth cert and a
+/// `ServerConfig`'s server cert
+pub struct CertifiedKeyResolver {
+  key: Arc<CertifiedKey>,
+}
+
+impl CertifiedKeyResolver {
+  /// Build a resolver serving `svid`'s leaf + chain + key
+  pub fn new(svid: &X509Svid) -> Result<Self, TlsError> {
+    let signing_key = rustls::crypto::ring::sign::any_supported_type(svid.private_key())
+      .map_err(|e| TlsError::General(format!("Unsupported private key: {}", e)))?;
+    let key = CertifiedKey::new(svid.cert_chain().to_vec(), signing_key);
+    Ok(Self { key: Arc::new(key) })
+  }
+}
+
+impl std::fmt::Debug for CertifiedKeyResolver {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("CertifiedKeyResolver").finish_non_exhaustive()
+  }
+}
+
+impl ResolvesServerCert for CertifiedKeyResolver {
+  fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+    Some(self.key.clone())
+  }
+}
+
+impl ResolvesClientCert for CertifiedKeyResolver {
+  fn resolve(
+    &self,
+    _root_hint_subjects: &[&[u8]],
+    _sigschemes: &[SignatureScheme],
+  ) -> Option<Arc<CertifiedKey>> {
+    Some(self.key.clone())
+  }
+
+  fn has_certs(&self) -> bool {
+    true
+  }
+}
@@ -1,26 +1,130 @@
 use crate::error::SpiffeError;
 use crate::proto::spire::api::types::Bundle;
 use der_parser::{oid, oid::Oid};
+use hyper_util::rt::TokioIo;
 use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
 use rustls::{Error as TlsError, RootCertStore};
-use rustls::{DigitallySignedStruct, SignatureScheme};
-use rustls_pki_types::{CertificateDer, ServerName};
+use rustls::{DigitallySignedStruct, DistinguishedName, SignatureScheme};
+use rustls_pki_types::{CertificateDer, ServerName, UnixTime};
+use std::io::Cursor;
 use std::sync::Arc;
-use tonic::transport::{Channel, ClientTlsConfig, Identity};
+use tokio_rustls::TlsConnector;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
 use x509_parser::prelude::*;
 
+/// ハンドシェイク中に抽出したSPIFFE IDを許可するかどうかを判定するポリシー
+pub trait SpiffeIdAuthorizer: std::fmt::Debug + Send + Sync {
+  fn authorize(&self, id: &SpiffeId) -> bool;
+}
+
+/// 解析済みのSPIFFE ID (trust domain + path)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SpiffeId {
+  trust_domain: String,
+  path: String,
+}
+
+impl SpiffeId {
+  pub fn parse(uri: &str) -> Result<Self, SpiffeError> {
+    let rest = uri
+      .strip_prefix("spiffe://")
+      .ok_or_else(|| SpiffeError::InvalidSpiffeId(uri.to_string()))?;
+
+    let mut parts = rest.splitn(2, '/');
+    let trust_domain = parts
+      .next()
+      .filter(|s| !s.is_empty())
+      .ok_or_else(|| SpiffeError::InvalidSpiffeId(uri.to_string()))?;
+    let path = parts.next().unwrap_or("");
+
+    Ok(Self {
+      trust_domain: trust_domain.to_string(),
+      path: format!("/{}", path),
+    })
+  }
+
+  pub fn trust_domain(&self) -> &str {
+    &self.trust_domain
+  }
+
+  pub fn path(&self) -> &str {
+    &self.path
+  }
+}
+
+/// 何でも許可する(トラストドメイン検証のみでよい用途向け)
+#[derive(Debug, Default)]
+pub struct AuthorizeAny;
+
+impl SpiffeIdAuthorizer for AuthorizeAny {
+  fn authorize(&self, _id: &SpiffeId) -> bool {
+    true
+  }
+}
+
+/// 特定の1つのSPIFFE IDのみ許可する
+#[derive(Debug)]
+pub struct AuthorizeId(pub SpiffeId);
+
+impl SpiffeIdAuthorizer for AuthorizeId {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    id == &self.0
+  }
+}
+
+/// 複数のSPIFFE IDのうちいずれかを許可する
+#[derive(Debug)]
+pub struct AuthorizeOneOf(pub Vec<SpiffeId>);
+
+impl SpiffeIdAuthorizer for AuthorizeOneOf {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    self.0.contains(id)
+  }
+}
+
+/// 指定したトラストドメインに属するすべてのIDを許可する
+#[derive(Debug)]
+pub struct AuthorizeMemberOf(pub String);
+
+impl SpiffeIdAuthorizer for AuthorizeMemberOf {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    id.trust_domain() == self.0
+  }
+}
+
+/// 指定したトラストドメイン配下で、パスが特定のプレフィックスに一致するIDを許可する
+#[derive(Debug)]
+pub struct AuthorizePathPrefix {
+  pub trust_domain: String,
+  pub prefix: String,
+}
+
+impl SpiffeIdAuthorizer for AuthorizePathPrefix {
+  fn authorize(&self, id: &SpiffeId) -> bool {
+    id.trust_domain() == self.trust_domain && id.path().starts_with(&self.prefix)
+  }
+}
+
 pub struct SpiffeChannelBuilder {
   trust_domain: String,
   bundle: Bundle,
   // オプション設定
   require_client_cert: bool,
   client_svid: Option<(Vec<u8>, Vec<u8>)>, // (cert, key)
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
 }
 
-#[derive(Debug)]
 struct SpiffeCertVerifier {
-  trust_domain: String,
   root_certs: RootCertStore,
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeCertVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SpiffeCertVerifier").finish_non_exhaustive()
+  }
 }
 
 impl ServerCertVerifier for SpiffeCertVerifier {
@@ -33,8 +137,11 @@ impl ServerCertVerifier for SpiffeCertVerifier {
     now: rustls_pki_types::UnixTime,
   ) -> Result<ServerCertVerified, TlsError> {
     // 1. SPIFFEカスタム検証
-    let spiffe_id = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
-    validate_spiffe_id(&spiffe_id, &self.trust_domain).map_err(|e| TlsError::General(e.to_string()))?;
+    let spiffe_id_uri = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
+    let spiffe_id = SpiffeId::parse(&spiffe_id_uri).map_err(|e| TlsError::General(e.to_string()))?;
+    if !self.authorizer.authorize(&spiffe_id) {
+      return Err(TlsError::General(format!("SPIFFE ID not authorized: {}", spiffe_id_uri)));
+    }
 
     // 2. 証明書チェーン検証のみrustlsに委譲
     let temp_verifier = rustls::client::WebPkiServerVerifier::builder(
@@ -77,9 +184,134 @@ impl ServerCertVerifier for SpiffeCertVerifier {
   }
 }
 
+// サーバー側: クライアント証明書をSPIFFEとして検証するverifier
+struct SpiffeClientCertVerifier {
+  root_certs: RootCertStore,
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl std::fmt::Debug for SpiffeClientCertVerifier {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("SpiffeClientCertVerifier").finish_non_exhaustive()
+  }
+}
+
+impl ClientCertVerifier for SpiffeClientCertVerifier {
+  fn offer_client_auth(&self) -> bool {
+    true
+  }
+
+  fn client_auth_mandatory(&self) -> bool {
+    true
+  }
+
+  fn root_hint_subjects(&self) -> &[DistinguishedName] {
+    &[]
+  }
+
+  fn verify_client_cert(
+    &self,
+    end_entity: &CertificateDer,
+    intermediates: &[CertificateDer],
+    now: UnixTime,
+  ) -> Result<ClientCertVerified, TlsError> {
+    // 1. SPIFFEカスタム検証(クライアント証明書にSPIFFE IDが含まれているか)
+    let spiffe_id_uri = extract_spiffe_id(end_entity).map_err(|e| TlsError::General(e.to_string()))?;
+    let spiffe_id = SpiffeId::parse(&spiffe_id_uri).map_err(|e| TlsError::General(e.to_string()))?;
+    if !self.authorizer.authorize(&spiffe_id) {
+      return Err(TlsError::General(format!("SPIFFE ID not authorized: {}", spiffe_id_uri)));
+    }
+
+    // 2. 証明書チェーン検証のみrustlsに委譲
+    let temp_verifier = rustls::server::WebPkiClientVerifier::builder(
+      Arc::new(self.root_certs.clone())
+    ).build().map_err(|e| TlsError::General(format!("Failed to build client verifier: {}", e)))?;
+
+    temp_verifier.verify_client_cert(end_entity, intermediates, now)?;
+
+    Ok(ClientCertVerified::assertion())
+  }
+
+  fn verify_tls12_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, TlsError> {
+    let default_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(self.root_certs.clone())).build().unwrap();
+
+    default_verifier.verify_tls12_signature(message, cert, dss)
+  }
+
+  fn verify_tls13_signature(&self, message: &[u8], cert: &CertificateDer<'_>, dss: &DigitallySignedStruct) -> Result<HandshakeSignatureValid, TlsError> {
+    let default_verifier = rustls::server::WebPkiClientVerifier::builder(Arc::new(self.root_certs.clone())).build().unwrap();
+
+    default_verifier.verify_tls13_signature(message, cert, dss)
+  }
+
+  fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+    vec![
+      SignatureScheme::RSA_PSS_SHA256,
+      SignatureScheme::RSA_PSS_SHA384,
+      SignatureScheme::RSA_PSS_SHA512,
+      SignatureScheme::ECDSA_NISTP256_SHA256,
+      SignatureScheme::ECDSA_NISTP384_SHA384,
+    ]
+  }
+}
+
+/// mTLSを終端するアクセプタ側のビルダー。`SpiffeChannelBuilder`のサーバー版で、
+/// 提示されたクライアント証明書をSPIFFE IDとして検証したうえで`ServerConfig`を組み立てる。
+pub struct SpiffeAcceptorBuilder {
+  bundle: Bundle,
+  server_svid: (Vec<u8>, Vec<u8>), // (cert, key)
+  authorizer: Arc<dyn SpiffeIdAuthorizer>,
+}
+
+impl SpiffeAcceptorBuilder {
+  pub fn new(trust_domain: String, bundle: Bundle, server_cert: Vec<u8>, server_key: Vec<u8>) -> Self {
+    Self {
+      bundle,
+      server_svid: (server_cert, server_key),
+      authorizer: Arc::new(AuthorizeMemberOf(trust_domain)),
+    }
+  }
+
+  /// 受け入れるSPIFFE IDのポリシーを差し替える
+  pub fn with_authorizer(mut self, authorizer: Arc<dyn SpiffeIdAuthorizer>) -> Self {
+    self.authorizer = authorizer;
+    self
+  }
+
+  /// クライアント証明書からSPIFFE IDを検証するrustlsの`ServerConfig`を組み立てる
+  pub fn build_server_config(&self) -> Result<rustls::ServerConfig, anyhow::Error> {
+    let mut root_store = RootCertStore::empty();
+    for auth in &self.bundle.x509_authorities {
+      root_store.add(CertificateDer::from(auth.asn1.clone()))?;
+    }
+
+    let verifier = Arc::new(SpiffeClientCertVerifier {
+      root_certs: root_store,
+      authorizer: self.authorizer.clone(),
+    });
+
+    let (cert, key) = &self.server_svid;
+    let cert_chain = vec![CertificateDer::from(cert.clone())];
+    let private_key = rustls_pki_types::PrivateKeyDer::try_from(key.clone())
+      .map_err(|_| anyhow::anyhow!("Failed to parse server private key"))?;
+
+    let config = rustls::ServerConfig::builder()
+      .with_client_cert_verifier(verifier)
+      .with_single_cert(cert_chain, private_key)?;
+
+    Ok(config)
+  }
+
+  /// 検証済みのクライアントのSPIFFE IDを取得する(ハンドシェイク後、アプリケーション層向け)
+  pub fn peer_spiffe_id(cert: &CertificateDer) -> Result<String, SpiffeError> {
+    extract_spiffe_id(cert)
+  }
+}
+
 impl SpiffeChannelBuilder {
   pub fn new(trust_domain: String, bundle: Bundle) -> Self {
     Self {
+      authorizer: Arc::new(AuthorizeMemberOf(trust_domain.clone())),
       trust_domain,
       bundle,
       require_client_cert: false,
@@ -93,31 +325,76 @@ impl SpiffeChannelBuilder {
     self
   }
 
+  /// 接続先サーバーのSPIFFE IDを検証するポリシーを差し替える
+  pub fn with_authorizer(mut self, authorizer: Arc<dyn SpiffeIdAuthorizer>) -> Self {
+    self.authorizer = authorizer;
+    self
+  }
+
+  /// `endpoint`への接続を確立する。tonicの`ClientTlsConfig`はカスタムの
+  /// `ServerCertVerifier`を受け付けられないため、TLSハンドシェイクは生の
+  /// rustls `ClientConfig`（`SpiffeCertVerifier`搭載）で`connect_with_connector`
+  /// の中から直接行う。こうしないと`SpiffeCertVerifier`は一度も実行されない。
   pub async fn connect(&self, endpoint: String) -> Result<Channel, anyhow::Error> {
-    let tls_config = self.build_tls_config()?;
+    let (connector, server_name) = self.build_tls_connector()?;
 
-    Channel::from_shared(endpoint)?.tls_config(tls_config)?.connect().await.map_err(Into::into)
+    let target: Uri = endpoint.parse()?;
+    let host = target
+      .host()
+      .ok_or_else(|| anyhow::anyhow!("Endpoint is missing a host: {}", endpoint))?
+      .to_string();
+    let port = target.port_u16().unwrap_or(443);
+
+    let channel = Endpoint::from_shared(endpoint)?
+      .connect_with_connector(service_fn(move |_: Uri| {
+        let host = host.clone();
+        let connector = connector.clone();
+        let server_name = server_name.clone();
+        async move {
+          let stream = tokio::net::TcpStream::connect((host.as_str(), port)).await?;
+          let tls_stream = connector.connect(server_name, stream).await?;
+          Ok::<_, std::io::Error>(TokioIo::new(tls_stream))
+        }
+      }))
+      .await?;
+
+    Ok(channel)
   }
 
-  fn build_tls_config(&self) -> Result<ClientTlsConfig, anyhow::Error> {
+  /// `SpiffeCertVerifier`を搭載した生のrustls `TlsConnector`を組み立てる
+  fn build_tls_connector(&self) -> Result<(TlsConnector, ServerName<'static>), anyhow::Error> {
     // 1. Bundleから証明書を抽出
-    let _ca_certs = self.extract_ca_certificates()?;
+    let ca_certs = self.extract_ca_certificates()?;
+    let mut root_store = RootCertStore::empty();
+    for ca_cert in ca_certs {
+      root_store.add(ca_cert)?;
+    }
 
-    // 2. SPIFFE証明書検証器を作成
-    let _verifier = SpiffeCertVerifier {
-      trust_domain: self.trust_domain.clone(),
-      root_certs: RootCertStore::empty(),
-    };
+    // 2. SPIFFE証明書検証器を作成(トラストドメイン一致だけでなく、任意のポリシーでSPIFFE IDを検証できる)
+    let verifier = Arc::new(SpiffeCertVerifier {
+      root_certs: root_store,
+      authorizer: self.authorizer.clone(),
+    });
 
-    // 3. tonicのTLS設定に変換
-    let mut tls = ClientTlsConfig::new().domain_name(&self.trust_domain);
+    let builder = rustls::ClientConfig::builder().dangerous().with_custom_certificate_verifier(verifier);
 
-    if let Some((cert, key)) = &self.client_svid {
-      let identity = Identity::from_pem(cert.clone(), key.clone());
-      tls = tls.identity(identity);
-    }
+    let tls_config = if let Some((cert_pem, key_pem)) = &self.client_svid {
+      let cert_chain = rustls_pemfile::certs(&mut Cursor::new(cert_pem))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| anyhow::anyhow!("Failed to parse client certificate PEM: {}", e))?;
+      let private_key = rustls_pemfile::private_key(&mut Cursor::new(key_pem))
+        .map_err(|e| anyhow::anyhow!("Failed to parse client private key PEM: {}", e))?
+        .ok_or_else(|| anyhow::anyhow!("Client private key PEM contains no key"))?;
+
+      builder.with_client_auth_cert(cert_chain, private_key)?
+    } else {
+      builder.with_no_client_auth()
+    };
+
+    let server_name = ServerName::try_from(self.trust_domain.clone())
+      .map_err(|_| anyhow::anyhow!("Invalid trust domain: {}", self.trust_domain))?;
 
-    Ok(tls)
+    Ok((TlsConnector::from(Arc::new(tls_config)), server_name))
   }
 
   fn extract_ca_certificates(&self) -> Result<Vec<CertificateDer<'static>>, anyhow::Error> {
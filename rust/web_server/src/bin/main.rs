@@ -4,7 +4,9 @@ use std::net::TcpStream;
 use std::sync::Arc;
 use clap::Parser;
 use std::error::Error;
-use web_server::{ThreadPool, JwtValidator, ValidationError, extract_jwt_from_header};
+use web_server::{ThreadPool, JwtValidator, SpiffeAuthError, authenticate, extract_jwt_from_header};
+use spire_client::proto::spire::api::types::Bundle;
+use spire_client::tls::{AuthorizeId, AuthorizeMemberOf, SpiffeId, SpiffeIdAuthorizer};
 
 // 起動フラグの定義
 #[derive(Parser, Debug)]
@@ -18,9 +20,34 @@ struct Args {
 
     #[arg(short, long, default_value = "jwk/jwk.json")]
     jwk_file: String,
+
+    // JWT-SVIDの`aud`クレームに要求する値
+    #[arg(long)]
+    audience: String,
+
+    // 許可するSPIFFE ID。`spiffe://...`形式なら完全一致、それ以外はトラストドメインとして扱う
+    #[arg(long)]
+    authorized_id: String,
+}
+
+// `--authorized-id`を完全一致(`AuthorizeId`)かトラストドメイン一致(`AuthorizeMemberOf`)かに解釈する
+fn parse_authorizer(spec: &str) -> Arc<dyn SpiffeIdAuthorizer> {
+    match SpiffeId::parse(spec) {
+        Ok(id) => Arc::new(AuthorizeId(id)),
+        Err(_) => Arc::new(AuthorizeMemberOf(spec.to_string())),
+    }
 }
 
-fn handle_client(mut stream: TcpStream, validator: &JwtValidator) {
+// `--authorized-id`からトラストドメインだけを取り出す。`spiffe://...`形式ならその中のトラストドメイン、
+// そうでなければ指定された文字列自体をトラストドメインとして扱う
+fn authorized_trust_domain(spec: &str) -> String {
+    match SpiffeId::parse(spec) {
+        Ok(id) => id.trust_domain().to_string(),
+        Err(_) => spec.to_string(),
+    }
+}
+
+fn handle_client(mut stream: TcpStream, bundle: &Bundle, audience: &str, authorizer: &dyn SpiffeIdAuthorizer) {
     let mut buffer = [0; 1024];
     match stream.read(&mut buffer) {
         Ok(size) => {
@@ -31,7 +58,7 @@ fn handle_client(mut stream: TcpStream, validator: &JwtValidator) {
             let response = match extract_jwt_from_header(&request) {
                 Some(token) => {
                     println!("抽出されたトークン: {}", token);
-                    match validator.validate(token) {
+                    match authenticate(token, bundle, audience, authorizer) {
                         Ok(claims) => {
                             println!("検証成功。クレーム: {:?}", claims);
                             "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\n\r\n認証成功\r\n"
@@ -39,11 +66,12 @@ fn handle_client(mut stream: TcpStream, validator: &JwtValidator) {
                         Err(e) => {
                             println!("検証エラー: {:?}", e);
                             match e {
-                                ValidationError::TokenExpired =>
-                                    "HTTP/1.1 401 Unauthorized\r\nContent-Type: text/plain\r\n\r\nトークンの有効期限が切れています\r\n",
-                                ValidationError::InvalidSignature =>
-                                    "HTTP/1.1 401 Unauthorized\r\nContent-Type: text/plain\r\n\r\n無効な署名です\r\n",
-                                _ => "HTTP/1.1 401 Unauthorized\r\nContent-Type: text/plain\r\n\r\n無効なトークンです\r\n",
+                                SpiffeAuthError::AudienceMismatch =>
+                                    "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\n\r\n要求されたaudienceがトークンに含まれていません\r\n",
+                                SpiffeAuthError::Unauthorized(_) =>
+                                    "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\n\r\nそのSPIFFE IDはこのエンドポイントで許可されていません\r\n",
+                                SpiffeAuthError::InvalidToken(_) =>
+                                    "HTTP/1.1 401 Unauthorized\r\nContent-Type: text/plain\r\n\r\n無効なトークンです\r\n",
                             }
                         }
                     }
@@ -64,13 +92,23 @@ fn handle_client(mut stream: TcpStream, validator: &JwtValidator) {
 fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
 
-
     let validator = JwtValidator::from_file(&args.jwk_file)
         .map_err(|e| {
             eprintln!("エラー: {}", e);
             std::process::exit(1);
         })?;
 
+    let authorizer = parse_authorizer(&args.authorized_id);
+    let trust_domain = authorized_trust_domain(&args.authorized_id);
+    let bundle = Arc::new(
+        validator
+            .to_jwt_bundle(&trust_domain)
+            .map_err(|e| {
+                eprintln!("エラー: {}", e);
+                std::process::exit(1);
+            })?,
+    );
+
     let addr = format!("{}:{}", args.host, args.port);
     let listener = TcpListener::bind(&addr)
         .expect("サーバーの起動に失敗しました");
@@ -78,14 +116,15 @@ fn main() -> Result<(), Box<dyn Error>> {
     println!("Server running on http://{}", addr);
 
     let pool = ThreadPool::new(10);
-    let validator = Arc::new(validator);
 
     for stream in listener.incoming() {
         match stream {
             Ok(stream) => {
-                let validator = Arc::clone(&validator); // validatorのクローンを作成
+                let bundle = Arc::clone(&bundle);
+                let audience = args.audience.clone();
+                let authorizer = Arc::clone(&authorizer);
                 pool.execute(move || {
-                    handle_client(stream, &validator);
+                    handle_client(stream, &bundle, &audience, authorizer.as_ref());
                 });
             }
             Err(e) => {
@@ -1,9 +1,10 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::Path;
 use std::error::Error;
 use std::time::{SystemTime, UNIX_EPOCH};
 use serde::{Deserialize, Serialize};
-use jsonwebtoken::{decode, DecodingKey, Validation, Algorithm};
+use jsonwebtoken::{decode, decode_header, DecodingKey, Validation, Algorithm};
 use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
 use p256::{
     ecdsa::VerifyingKey,
@@ -33,6 +34,15 @@ pub struct Jwk {
     kid: Option<String>,
 }
 
+// JWKS（JWK Set）ドキュメント。鍵のローテーション中は複数の鍵が同時に有効になりうる
+#[derive(Debug, Serialize, Deserialize)]
+struct Jwks {
+    keys: Vec<Jwk>,
+}
+
+// kidを持たない鍵をマップに格納する際のプレースホルダーキー
+const UNKEYED_KID: &str = "";
+
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
     #[error("トークンの有効期限が切れています")]
@@ -43,19 +53,33 @@ pub enum ValidationError {
     FileReadError(#[from] std::io::Error),
     #[error("鍵のフォーマットが無効です")]
     InvalidKeyFormat,
+    #[error("トークンのヘッダーを解析できません")]
+    InvalidHeader,
+    #[error("指定されたkidに一致する鍵が見つかりません: {0}")]
+    KeyNotFound(String),
 }
 
 pub struct JwtValidator {
-    jwk: Jwk,
+    // kidごとに鍵を保持することで、ローテーション中の新旧鍵を両方受け入れる
+    keys: HashMap<String, Jwk>,
     validation: Validation,
 }
 
 impl JwtValidator {
-    pub fn new(jwk_json: &str) -> Result<Self, ValidationError> {
-        let jwk: Jwk = serde_json::from_str(jwk_json)
-            .map_err(|_| ValidationError::InvalidKeyFormat)?;
+    pub fn new(jwks_json: &str) -> Result<Self, ValidationError> {
+        let jwks = Self::parse_jwks(jwks_json)?;
+
+        let mut keys = HashMap::with_capacity(jwks.keys.len());
+        for jwk in jwks.keys {
+            if jwk.kty != "EC" || jwk.crv != "P-256" {
+                return Err(ValidationError::InvalidKeyFormat);
+            }
+
+            let kid = jwk.kid.clone().unwrap_or_else(|| UNKEYED_KID.to_string());
+            keys.insert(kid, jwk);
+        }
 
-        if jwk.kty != "EC" || jwk.crv != "P-256" {
+        if keys.is_empty() {
             return Err(ValidationError::InvalidKeyFormat);
         }
 
@@ -63,18 +87,38 @@ impl JwtValidator {
         validation.validate_exp = true;
         validation.set_audience(&["web_server"]); // 必要に応じて変更
 
-        Ok(Self { jwk, validation })
+        Ok(Self { keys, validation })
+    }
+
+    // トップレベルが`keys`配列(JWKS)か、単一のJWKかを両方受け入れる
+    fn parse_jwks(jwks_json: &str) -> Result<Jwks, ValidationError> {
+        if let Ok(jwks) = serde_json::from_str::<Jwks>(jwks_json) {
+            return Ok(jwks);
+        }
+
+        let jwk: Jwk = serde_json::from_str(jwks_json)
+            .map_err(|_| ValidationError::InvalidKeyFormat)?;
+        Ok(Jwks { keys: vec![jwk] })
     }
 
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ValidationError> {
-        let jwk_json = fs::read_to_string(path)
+        let jwks_json = fs::read_to_string(path)
             .map_err(|e| ValidationError::FileReadError(e))?;
 
-        Self::new(&jwk_json)
+        Self::new(&jwks_json)
+    }
+
+    // 鍵セットを差し替える。ローテーション後の新しいJWKS文書を定期的に読み込む用途を想定
+    pub fn reload(&mut self, jwks_json: &str) -> Result<(), ValidationError> {
+        let updated = Self::new(jwks_json)?;
+        self.keys = updated.keys;
+        Ok(())
     }
 
     pub fn validate(&self, token: &str) -> Result<Claims, ValidationError> {
-        let decoding_key = match self.create_decoding_key() {
+        let jwk = self.select_key(token)?;
+
+        let decoding_key = match Self::create_decoding_key(jwk) {
             Ok(key) => key,
             Err(e) => {
                 println!("デコーディングキーの作成エラー: {:?}", e);
@@ -104,11 +148,32 @@ impl JwtValidator {
         Ok(token_data.claims)
     }
 
-    fn create_decoding_key(&self) -> Result<DecodingKey, Box<dyn Error>> {
-        println!("JWKデータ: x={}, y={}", self.jwk.x, self.jwk.y);
+    // トークンヘッダーのkidに一致する鍵を選ぶ。kidがない場合は登録されている鍵が1つだけならそれを使う
+    fn select_key(&self, token: &str) -> Result<&Jwk, ValidationError> {
+        let header = decode_header(token).map_err(|_| ValidationError::InvalidHeader)?;
+
+        match header.kid {
+            Some(kid) => self.keys.get(&kid).ok_or(ValidationError::KeyNotFound(kid)),
+            None => {
+                self.keys.get(UNKEYED_KID)
+                    .or_else(|| if self.keys.len() == 1 { self.keys.values().next() } else { None })
+                    .ok_or_else(|| ValidationError::KeyNotFound("(no kid in token)".to_string()))
+            }
+        }
+    }
+
+    fn create_decoding_key(jwk: &Jwk) -> Result<DecodingKey, Box<dyn Error>> {
+        let verify_key = Self::jwk_to_verifying_key(jwk)?;
+        Ok(DecodingKey::from_ec_der(&verify_key.to_sec1_bytes()))
+    }
+
+    // JWKのx/y座標（base64）からEC公開鍵を組み立てる。鍵のSEC1表現が必要な
+    // 呼び出し元（`create_decoding_key`、`to_jwt_bundle`）で共有する
+    fn jwk_to_verifying_key(jwk: &Jwk) -> Result<VerifyingKey, Box<dyn Error>> {
+        println!("JWKデータ: x={}, y={}", jwk.x, jwk.y);
 
-        let x_vec = URL_SAFE_NO_PAD.decode(&self.jwk.x)?;
-        let y_vec = URL_SAFE_NO_PAD.decode(&self.jwk.y)?;
+        let x_vec = URL_SAFE_NO_PAD.decode(&jwk.x)?;
+        let y_vec = URL_SAFE_NO_PAD.decode(&jwk.y)?;
 
         println!("デコード後のバイト長: x={}, y={}", x_vec.len(), y_vec.len());
 
@@ -119,10 +184,33 @@ impl JwtValidator {
         let y = FieldBytes::from(y_bytes);
 
         let point = EncodedPoint::from_affine_coordinates(&x, &y, false);
-        let verify_key = VerifyingKey::from_encoded_point(&point)?;
-        let key_bytes = verify_key.to_sec1_bytes();
+        Ok(VerifyingKey::from_encoded_point(&point)?)
+    }
+
+    // 保持している鍵セットをJWT-SVID署名検証（`spire_client::svid::JwtSvid::verify_signature`）が
+    // 期待する`Bundle`形式に変換する。鍵のローテーション中は複数のkidが並存しうる
+    pub fn to_jwt_bundle(
+        &self,
+        trust_domain: &str,
+    ) -> Result<spire_client::proto::spire::api::types::Bundle, ValidationError> {
+        let mut jwt_authorities = Vec::with_capacity(self.keys.len());
+        for (kid, jwk) in &self.keys {
+            let verify_key = Self::jwk_to_verifying_key(jwk).map_err(|_| ValidationError::InvalidKeyFormat)?;
+            jwt_authorities.push(spire_client::proto::spire::api::types::JwtKey {
+                public_key: verify_key.to_sec1_bytes().to_vec(),
+                key_id: kid.clone(),
+                expires_at: 0,
+                tainted: false,
+            });
+        }
 
-        Ok(DecodingKey::from_ec_der(&key_bytes))
+        Ok(spire_client::proto::spire::api::types::Bundle {
+            trust_domain: trust_domain.to_string(),
+            x509_authorities: vec![],
+            jwt_authorities,
+            refresh_hint: 0,
+            sequence_number: 0,
+        })
     }
 }
 
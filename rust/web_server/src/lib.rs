@@ -1,5 +1,7 @@
 mod jwt;
 mod pool;
+mod spiffe_auth;
 
 pub use jwt::{JwtValidator, Claims, ValidationError, extract_jwt_from_header};
 pub use pool::ThreadPool;
+pub use spiffe_auth::{authenticate, SpiffeAuthError};